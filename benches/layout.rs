@@ -0,0 +1,27 @@
+//! Compare the time to tick a large universe stored in each layout.
+//!
+//! Run with `cargo bench --bench layout`.
+
+use std::time::Instant;
+
+use wasm_game_of_life::{Layout, UniverseBuilder};
+
+const SIZE: u32 = 1024;
+const TICKS: u32 = 20;
+
+fn main() {
+	for layout in [Layout::RowMajor, Layout::Tiled] {
+		let mut universe = UniverseBuilder::new()
+			.size(SIZE, SIZE)
+			.layout(layout)
+			.random(0.3)
+			.seed(1)
+			.build()
+			.unwrap();
+
+		let start = Instant::now();
+		universe.tick_many(TICKS);
+		let elapsed = start.elapsed();
+		println!("{:?}: {:?} per tick on {}x{}", layout, elapsed / TICKS, SIZE, SIZE);
+	}
+}
@@ -0,0 +1,43 @@
+//! Compare the time to count the neighbors of every cell with the lookup table and with a `get_cell` call per neighbor.
+//!
+//! Run with `cargo bench --bench naive`.
+
+use std::time::Instant;
+
+use wasm_game_of_life::{Universe, UniverseBuilder};
+
+const SIZE: u32 = 256;
+const ROUNDS: u32 = 20;
+
+/// Count the living neighbors of a cell by testing each of them, wrapping around the edges.
+fn count_individually(universe: &Universe, x: u32, y: u32) -> u32 {
+	let mut count = 0;
+	for dy in [SIZE - 1, 0, 1] {
+		for dx in [SIZE - 1, 0, 1] {
+			if dx != 0 || dy != 0 {
+				count += universe.get_cell((x + dx) % SIZE, (y + dy) % SIZE) as u32;
+			}
+		}
+	}
+	count
+}
+
+/// Time summing provided neighbor count over every cell of the universe, returning the sum.
+fn time<F>(name: &str, universe: &Universe, count: F) -> u32
+where F: Fn(&Universe, u32, u32) -> u32 {
+	let start = Instant::now();
+	let mut sum = 0;
+	for _ in 0..ROUNDS {
+		sum = (0..SIZE).flat_map(|y| (0..SIZE).map(move |x| (x, y))).map(|(x, y)| count(universe, x, y)).sum();
+	}
+	println!("{}: {:?} per round on {}x{}", name, start.elapsed() / ROUNDS, SIZE, SIZE);
+	sum
+}
+
+fn main() {
+	let universe = UniverseBuilder::new().size(SIZE, SIZE).random(0.3).seed(1).build().unwrap();
+
+	let individually = time("get_cell", &universe, count_individually);
+	let table = time("table", &universe, Universe::neighbor_count);
+	assert_eq!(individually, table);
+}
@@ -1,52 +1,344 @@
-use js_sys::Math;
+use wasm_bindgen::prelude::*;
+
+use crate::rng::Rng;
+
+/// Order of bits within each byte of raw data.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitOrder {
+	/// The first bit of a byte is its least significant one.
+	LsbFirst,
+	/// The first bit of a byte is its most significant one, as in formats like PBM.
+	MsbFirst,
+}
 
 /// Bit-dense storage for cells.
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct BitStore(Vec<u8>);
+///
+/// Only the first `valid_bits` bits are meaningful, the rest of the last byte is padding.
+/// Bits are stored least significant first, so bit `idx` is `bytes[idx / 8] >> (idx % 8) & 1`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BitStore {
+	bytes: Vec<u8>,
+	valid_bits: usize,
+}
 
 impl BitStore {
 	/// Test whether the provided bit is set.
 	pub fn get(&self, idx: usize) -> bool {
 		let mask = 1 << (idx % 8);
-		self.0[idx/8] & mask == mask 
+		self.bytes[idx/8] & mask == mask 
 	}
 
 	/// Set the provided bit to given state.
 	pub fn set(&mut self, idx: usize, val: bool) {
 		let mask = 1 << (idx % 8);
 		if val {
-			self.0[idx/8] |= mask
+			self.bytes[idx/8] |= mask
 		} else {
-			self.0[idx/8] &= !mask
+			self.bytes[idx/8] &= !mask
+		}
+	}
+
+	/// Unset all bits.
+	pub fn clear(&mut self) {
+		self.bytes.iter_mut().for_each(|byte| *byte = 0);
+	}
+
+	/// Flip every valid bit, leaving padding unset.
+	pub fn invert(&mut self) {
+		self.bytes.iter_mut().for_each(|byte| *byte = !*byte);
+		self.trim_padding(self.valid_bits);
+	}
+
+	/// Combine the bytes of two equally sized stores using provided operation.
+	fn combine<F>(&self, other: &Self, op: F) -> Self
+	where F: Fn(u8, u8) -> u8 {
+		assert_eq!(self.size(), other.size(), "combined stores should be the same size");
+		Self {
+			bytes: self.bytes.iter().zip(&other.bytes).map(|(&a, &b)| op(a, b)).collect(),
+			valid_bits: self.valid_bits,
+		}
+	}
+
+	/// Get a store with bits set in both stores.
+	///
+	/// Panics if the stores are of different size.
+	pub fn and(&self, other: &Self) -> Self {
+		self.combine(other, |a, b| a & b)
+	}
+
+	/// Get a store with bits set in either store.
+	///
+	/// Panics if the stores are of different size.
+	pub fn or(&self, other: &Self) -> Self {
+		self.combine(other, |a, b| a | b)
+	}
+
+	/// Get a store with bits that differ between the stores.
+	///
+	/// Panics if the stores are of different size.
+	pub fn xor(&self, other: &Self) -> Self {
+		self.combine(other, |a, b| a ^ b)
+	}
+
+	/// Iterate over the states of valid bits in order.
+	pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+		(0..self.valid_bits).map(move |idx| self.get(idx))
+	}
+
+	/// Iterate over the indices of set bits in ascending order.
+	pub fn iter_set_bits(&self) -> impl Iterator<Item = usize> + '_ {
+		self.bytes.iter()
+			.enumerate()
+			.filter(|(_, &byte)| byte != 0)
+			.flat_map(|(i, &byte)| (0..8)
+				.filter(move |bit| byte >> bit & 1 == 1)
+				.map(move |bit| i * 8 + bit))
+	}
+
+	/// Get a store with every valid bit moved provided number of bits towards the end, wrapping around.
+	pub fn shift_right(&self, bits: usize) -> Self {
+		let mut result = Self::empty(self.valid_bits);
+		if self.valid_bits == 0 {
+			return result;
+		}
+		let bits = bits % self.valid_bits;
+		for idx in self.iter_set_bits().take_while(|&idx| idx < self.valid_bits) {
+			result.set((idx + bits) % self.valid_bits, true);
+		}
+		result
+	}
+
+	/// Get a store with every valid bit moved provided number of bits towards the start, wrapping around.
+	pub fn shift_left(&self, bits: usize) -> Self {
+		if self.valid_bits == 0 {
+			return self.clone();
+		}
+		self.shift_right(self.valid_bits - bits % self.valid_bits)
+	}
+
+	/// Get the mask of valid bits within the byte at provided index.
+	fn valid_mask(&self, byte: usize) -> u8 {
+		match self.valid_bits.saturating_sub(byte * 8) {
+			0 => 0,
+			bits if bits >= 8 => 0xFF,
+			bits => !(0xFF << bits),
+		}
+	}
+
+	/// Get the number of valid bits that differ between the stores.
+	fn differing_bits(&self, other: &Self) -> usize {
+		self.bytes.iter()
+			.zip(&other.bytes)
+			.enumerate()
+			.map(|(i, (a, b))| ((a ^ b) & self.valid_mask(i)).count_ones() as usize)
+			.sum()
+	}
+
+	/// Get the number of valid bits that differ between the stores.
+	///
+	/// Panics if the stores are of different size.
+	pub fn hamming_distance(&self, other: &Self) -> usize {
+		assert_eq!(self.size(), other.size(), "compared stores should be the same size");
+		self.differing_bits(other)
+	}
+
+	/// Test whether the provided bit is set, or get `None` if it is out of bounds.
+	pub fn get_checked(&self, idx: usize) -> Option<bool> {
+		let mask = 1 << (idx % 8);
+		self.bytes.get(idx/8).map(|byte| byte & mask == mask)
+	}
+
+	/// Set the provided bit to given state, returning false if it is out of bounds.
+	pub fn set_checked(&mut self, idx: usize, val: bool) -> bool {
+		if idx/8 >= self.bytes.len() {
+			return false;
+		}
+		self.set(idx, val);
+		true
+	}
+
+	/// Set all provided bits to given state, skipping the ones that are out of bounds.
+	pub fn set_many(&mut self, indices: &[usize], val: bool) {
+		for &idx in indices {
+			self.set_checked(idx, val);
+		}
+	}
+
+	/// Get `len` bits starting at provided one as the lowest bits of a word, a byte at a time.
+	pub fn get_bits(&self, start: usize, len: usize) -> u64 {
+		debug_assert!(len <= 64);
+		let (mut bits, mut done) = (0, 0);
+		while done < len {
+			let idx = start + done;
+			let offset = idx % 8;
+			let count = (8 - offset).min(len - done);
+			let mask = (u16::MAX >> (16 - count)) as u8;
+			bits |= ((self.bytes[idx/8] >> offset & mask) as u64) << done;
+			done += count;
+		}
+		bits
+	}
+
+	/// Set `len` bits starting at provided one to the lowest bits of `bits`, a byte at a time.
+	pub fn set_bits(&mut self, start: usize, len: usize, bits: u64) {
+		debug_assert!(len <= 64);
+		let mut done = 0;
+		while done < len {
+			let idx = start + done;
+			let offset = idx % 8;
+			let count = (8 - offset).min(len - done);
+			let mask = (u16::MAX >> (16 - count)) as u8;
+			let byte = &mut self.bytes[idx/8];
+			*byte = *byte & !(mask << offset) | ((bits >> done) as u8 & mask) << offset;
+			done += count;
 		}
 	}
 
 	/// Get the number of bytes the data occupies.
 	pub fn size(&self) -> usize {
-		self.0.len()
+		self.bytes.len()
+	}
+
+	/// Get the number of addressable bits, including padding.
+	pub fn capacity_bits(&self) -> usize {
+		self.bytes.len() * 8
+	}
+
+	/// Get the number of meaningful bits.
+	pub fn valid_bits(&self) -> usize {
+		self.valid_bits
+	}
+
+	/// Get the underlying bytes.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.bytes
 	}
 
 	pub fn as_ptr(&self) -> *const u8 {
-		self.0.as_ptr()
+		self.bytes.as_ptr()
+	}
+
+	/// Get the number of set bits, including any padding bits in the last byte.
+	pub fn count_ones(&self) -> usize {
+		self.bytes.iter().map(|byte| byte.count_ones() as usize).sum()
+	}
+
+	/// Get the number of set bits within provided range of bytes, including any padding bits.
+	///
+	/// Note: the range is clamped to the bytes of the store.
+	pub fn count_ones_range(&self, byte_start: usize, byte_end: usize) -> usize {
+		let end = byte_end.min(self.bytes.len());
+		self.bytes[byte_start.min(end)..end].iter().map(|byte| byte.count_ones() as usize).sum()
 	}
 
 	/// Generate a randomly filled BitStore with at least the provided bit count.
+	///
+	/// Padding bits past the provided length are guaranteed to be unset.
 	pub fn random(length: usize) -> Self {
-		let rounding = (length % 8 != 0) as usize;
-		let max = length / 8 + rounding;
-		// NOTE: a more efficient variant would be to invoke Math::random() for every 50ish bits
-		// as it's the most computationally expensive operation during initialization.
-		Self((0..max)
-			.map(|_| (Math::random() * 256.0) as u8)
-			.collect()
-		)
+		Self::random_with(length, &mut Rng::from_entropy())
+	}
+
+	/// Generate a BitStore with at least the provided bit count filled deterministically from the seed.
+	pub fn random_seeded(length: usize, seed: u64) -> Self {
+		Self::random_with(length, &mut Rng::new(seed))
+	}
+
+	/// Generate a randomly filled BitStore with at least the provided bit count using provided generator.
+	pub fn random_with(length: usize, rng: &mut Rng) -> Self {
+		let max = length.div_ceil(8);
+		let mut bytes = Vec::with_capacity(max);
+		while bytes.len() < max {
+			let bits = rng.next_u64().to_le_bytes();
+			let count = bits.len().min(max - bytes.len());
+			bytes.extend_from_slice(&bits[..count]);
+		}
+		let mut result = Self::from_bytes(bytes);
+		result.trim_padding(length);
+		result
+	}
+
+	/// Mark provided number of bits as valid and unset all bits past them.
+	pub fn trim_padding(&mut self, valid_bits: usize) {
+		self.valid_bits = valid_bits.min(self.capacity_bits());
+		if let Some((partial, rest)) = self.bytes.get_mut(valid_bits / 8..).and_then(|b| b.split_first_mut()) {
+			*partial &= !(0xFF << (valid_bits % 8));
+			rest.iter_mut().for_each(|byte| *byte = 0);
+		}
+	}
+
+	/// Create a bitstore backed by provided bytes, all of which are valid.
+	pub fn from_bytes(bytes: Vec<u8>) -> Self {
+		let valid_bits = bytes.len() * 8;
+		Self { bytes, valid_bits }
+	}
+
+	/// Create a bitstore backed by provided bytes with bits in given order, all of which are valid.
+	pub fn from_bytes_with_order(mut bytes: Vec<u8>, order: BitOrder) -> Self {
+		if order == BitOrder::MsbFirst {
+			bytes.iter_mut().for_each(|byte| *byte = byte.reverse_bits());
+		}
+		Self::from_bytes(bytes)
+	}
+
+	/// Get the order of bits within the bytes of every store, see `as_bytes`.
+	pub fn bit_order() -> BitOrder {
+		BitOrder::LsbFirst
+	}
+
+	/// Take the underlying bytes.
+	pub fn into_bytes(self) -> Vec<u8> {
+		self.bytes
 	}
 
 	/// Create an empty bitstore with at least provided bit count.
 	pub fn empty(length: usize) -> Self {
-		let rounding = (length % 8 != 0) as usize;
-		let max = length / 8 + rounding;
-		Self((0..max).map(|_| 0).collect())
+		Self {
+			bytes: vec![0; length.div_ceil(8)],
+			valid_bits: length,
+		}
+	}
+}
+
+// Padding bits do not affect equality.
+impl PartialEq for BitStore {
+	fn eq(&self, other: &Self) -> bool {
+		self.valid_bits == other.valid_bits && self.differing_bits(other) == 0
+	}
+}
+
+impl Eq for BitStore {}
+
+// The bits are summarized, dumping them is unreadable for all but the smallest stores.
+impl std::fmt::Debug for BitStore {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("BitStore")
+			.field("bytes", &self.bytes.len())
+			.field("valid_bits", &self.valid_bits)
+			.field("set_bits", &self.iter_set_bits().take_while(|&idx| idx < self.valid_bits).count())
+			.finish()
+	}
+}
+
+// Bits are appended after the last valid one, growing the store as needed.
+impl Extend<bool> for BitStore {
+	fn extend<T: IntoIterator<Item = bool>>(&mut self, iter: T) {
+		for val in iter {
+			if self.valid_bits == self.capacity_bits() {
+				self.bytes.push(0);
+			}
+			self.set(self.valid_bits, val);
+			self.valid_bits += 1;
+		}
+	}
+}
+
+impl std::iter::FromIterator<bool> for BitStore {
+	fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+		let mut store = Self::empty(0);
+		store.extend(iter);
+		store
 	}
 }
 
@@ -54,14 +346,235 @@ impl BitStore {
 fn test_bitstore() {
 	let mut store = BitStore::empty(2);
 
-	assert_eq!(&store.0, &[0]);
+	assert_eq!(&store.bytes, &[0]);
 
 	store.set(0, true);
-	assert_eq!(&store.0, &[1]);
+	assert_eq!(&store.bytes, &[1]);
 
 	store.set(1, true);
-	assert_eq!(&store.0, &[3]);
+	assert_eq!(&store.bytes, &[3]);
 
 	store.set(0, false);
-	assert_eq!(&store.0, &[2]);
+	assert_eq!(&store.bytes, &[2]);
+}
+
+#[test]
+fn test_random_seeded() {
+	let a = BitStore::random_seeded(100, 7);
+	let b = BitStore::random_seeded(100, 7);
+	let c = BitStore::random_seeded(100, 8);
+
+	assert_eq!(a.size(), 13);
+	assert_eq!(a, b);
+	assert_ne!(a, c);
+}
+
+#[test]
+fn test_random_padding() {
+	for seed in 0..16 {
+		let store = BitStore::random_seeded(10, seed);
+		assert!((10..16).all(|idx| !store.get(idx)));
+	}
+}
+
+#[test]
+fn test_trim_padding() {
+	let mut store = BitStore::from_bytes(vec![0xFF, 0xFF, 0xFF]);
+	store.trim_padding(10);
+	assert_eq!(&store.bytes, &[0xFF, 0x03, 0x00]);
+	assert_eq!(store.valid_bits(), 10);
+
+	store.trim_padding(8);
+	assert_eq!(&store.bytes, &[0xFF, 0x00, 0x00]);
+
+	store.trim_padding(100);
+	assert_eq!(&store.bytes, &[0xFF, 0x00, 0x00]);
+	assert_eq!(store.valid_bits(), 24);
+}
+
+#[test]
+fn test_clear() {
+	let mut store = BitStore::random_seeded(20, 3);
+	store.clear();
+	assert_eq!(store, BitStore::empty(20));
+}
+
+#[test]
+fn test_bitwise_operators() {
+	let a = BitStore::from_bytes(vec![0b1100, 0xFF]);
+	let b = BitStore::from_bytes(vec![0b1010, 0x0F]);
+
+	assert_eq!(a.and(&b), BitStore::from_bytes(vec![0b1000, 0x0F]));
+	assert_eq!(a.or(&b), BitStore::from_bytes(vec![0b1110, 0xFF]));
+	assert_eq!(a.xor(&b), BitStore::from_bytes(vec![0b0110, 0xF0]));
+	assert_eq!(a.xor(&a), BitStore::empty(16));
+}
+
+#[test]
+#[should_panic]
+fn test_bitwise_operator_size_mismatch() {
+	BitStore::empty(8).or(&BitStore::empty(16));
+}
+
+#[test]
+fn test_hamming_distance() {
+	let a = BitStore::from_bytes(vec![0b1100, 0xFF]);
+	let b = BitStore::from_bytes(vec![0b1010, 0x0F]);
+
+	assert_eq!(a.hamming_distance(&a), 0);
+	assert_eq!(a.hamming_distance(&b), 6);
+	assert_eq!(b.hamming_distance(&a), 6);
+}
+
+#[test]
+fn test_iter_set_bits() {
+	let store = BitStore::from_bytes(vec![0b1000_0001, 0, 0, 0b10]);
+	assert_eq!(store.iter_set_bits().collect::<Vec<_>>(), &[0, 7, 25]);
+	assert_eq!(BitStore::empty(64).iter_set_bits().count(), 0);
+}
+
+#[test]
+fn test_checked_access() {
+	let mut store = BitStore::empty(10);
+
+	assert!(store.set_checked(9, true));
+	assert_eq!(store.get_checked(9), Some(true));
+	assert_eq!(store.get_checked(8), Some(false));
+
+	// padding bits are addressable, bits beyond the last byte are not
+	assert!(store.set_checked(15, true));
+	assert!(!store.set_checked(16, true));
+	assert_eq!(store.get_checked(16), None);
+	assert_eq!(store.get_checked(usize::MAX), None);
+	assert_eq!(&store.bytes, &[0, 0b1000_0010]);
+}
+
+#[test]
+fn test_valid_bits() {
+	let mut a = BitStore::empty(5);
+	let mut b = BitStore::empty(5);
+	a.set(2, true);
+	b.set(2, true);
+	assert_eq!(a.capacity_bits(), 8);
+	assert_eq!(a.valid_bits(), 5);
+
+	b.set(6, true);
+	assert_eq!(a, b);
+	assert_eq!(a.hamming_distance(&b), 0);
+
+	b.set(4, true);
+	assert_ne!(a, b);
+	assert_ne!(BitStore::empty(5), BitStore::empty(6));
+}
+
+#[test]
+fn test_bytes_round_trip() {
+	let store = BitStore::from_bytes(vec![0b101, 0xF0]);
+	assert!(store.get(0));
+	assert!(!store.get(1));
+	assert!(store.get(15));
+	assert_eq!(store.into_bytes(), &[0b101, 0xF0]);
+}
+
+#[test]
+fn test_count_ones() {
+	let mut store = BitStore::empty(12);
+	assert_eq!(store.count_ones(), 0);
+
+	store.set(0, true);
+	store.set(7, true);
+	store.set(8, true);
+	store.set(15, true);
+	assert_eq!(store.count_ones(), 4);
+}
+
+#[test]
+fn test_set_many() {
+	let mut store = BitStore::empty(16);
+	store.set_many(&[1, 3, 14, 16, usize::MAX], true);
+	assert_eq!(&store.bytes, &[0b1010, 0b0100_0000]);
+
+	store.set_many(&[3, 14], false);
+	assert_eq!(&store.bytes, &[0b10, 0]);
+}
+
+#[test]
+fn test_set_bits() {
+	let mut store = BitStore::empty(80);
+	store.set_bits(3, 64, u64::MAX);
+	assert_eq!(store.count_ones(), 64);
+	assert_eq!(store.iter_set_bits().next(), Some(3));
+
+	store.set_bits(5, 10, 0b10_0000_0001);
+	assert_eq!(&store.bytes[..3], &[0b0011_1000, 0b1100_0000, 0xFF]);
+	assert_eq!(store.get_bits(5, 10), 0b10_0000_0001);
+	assert_eq!(store.get_bits(0, 64), u64::MAX << 3 & !(0xFF << 6));
+}
+
+#[test]
+fn test_invert() {
+	let mut store = BitStore::from_bytes(vec![0b1010_0101, 0xFF]);
+	store.trim_padding(12);
+	store.invert();
+	assert_eq!(&store.bytes, &[0b0101_1010, 0]);
+
+	store.invert();
+	store.invert();
+	assert_eq!(store.count_ones(), 4);
+}
+
+#[test]
+fn test_shift() {
+	let mut store = BitStore::empty(10);
+	store.set(0, true);
+	store.set(8, true);
+
+	let right = store.shift_right(3);
+	assert_eq!(right.iter_set_bits().collect::<Vec<_>>(), &[1, 3]);
+	assert_eq!(right.shift_left(3), store);
+	assert_eq!(store.shift_left(1).iter_set_bits().collect::<Vec<_>>(), &[7, 9]);
+	assert_eq!(store.shift_right(20), store);
+}
+
+#[test]
+fn test_count_ones_range() {
+	let store = BitStore::from_bytes(vec![0xFF, 0b1010, 0, 0b1]);
+	assert_eq!(store.count_ones_range(0, 4), 11);
+	assert_eq!(store.count_ones_range(1, 3), 2);
+	assert_eq!(store.count_ones_range(2, 2), 0);
+	assert_eq!(store.count_ones_range(3, 100), 1);
+	assert_eq!(store.count_ones_range(5, 100), 0);
+	assert_eq!(store.count_ones_range(3, 1), 0);
+}
+
+#[test]
+fn test_collect_bits() {
+	let mut store: BitStore = [true, false, true].iter().copied().collect();
+	assert_eq!(store.valid_bits(), 3);
+	assert_eq!(store.as_bytes(), &[0b101]);
+	assert_eq!(store.iter().collect::<Vec<_>>(), &[true, false, true]);
+
+	store.extend(std::iter::repeat_n(true, 6));
+	assert_eq!(store.valid_bits(), 9);
+	assert_eq!(store.as_bytes(), &[0b1111_1101, 0b1]);
+	assert_eq!(store.iter().filter(|&bit| bit).count(), 8);
+}
+
+#[test]
+fn test_from_bytes_with_order() {
+	assert_eq!(BitStore::bit_order(), BitOrder::LsbFirst);
+
+	let store = BitStore::from_bytes_with_order(vec![0b1100_0001, 0b0000_0010], BitOrder::MsbFirst);
+	assert_eq!(store.iter_set_bits().collect::<Vec<_>>(), &[0, 1, 7, 14]);
+	assert_eq!(store.as_bytes(), &[0b1000_0011, 0b0100_0000]);
+
+	let store = BitStore::from_bytes_with_order(vec![0b1100_0001], BitOrder::LsbFirst);
+	assert_eq!(store.iter_set_bits().collect::<Vec<_>>(), &[0, 6, 7]);
+}
+
+#[test]
+fn test_debug() {
+	let mut store = BitStore::from_bytes(vec![0xFF, 0b1010]);
+	store.trim_padding(10);
+	assert_eq!(format!("{:?}", store), "BitStore { bytes: 2, valid_bits: 10, set_bits: 9 }");
 }
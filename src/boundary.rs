@@ -0,0 +1,24 @@
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+/// Behavior of the universe at its edges.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Boundary {
+	/// Edges wrap around, so the universe is a torus.
+	#[default]
+	Toroidal,
+	/// Cells beyond the edges are permanently dead.
+	Dead,
+}
+
+impl fmt::Display for Boundary {
+	/// Write the lowercase name of the boundary, for example `"toroidal"`.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Toroidal => write!(f, "toroidal"),
+			Self::Dead => write!(f, "dead"),
+		}
+	}
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+use crate::rng::Rng;
+use crate::rule::{Rule, RuleError};
+use crate::{shape, Boundary, Layout, Universe};
+
+/// Reasons a universe builder may fail to build.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BuildError {
+	/// The size of the universe was not set.
+	MissingSize,
+	/// The rule could not be parsed.
+	Rule(RuleError),
+	/// There is no pattern with provided name.
+	UnknownPattern(String),
+}
+
+impl fmt::Display for BuildError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::MissingSize => write!(f, "universe size was not set"),
+			Self::Rule(err) => write!(f, "{}", err),
+			Self::UnknownPattern(name) => write!(f, "unknown pattern '{}'", name),
+		}
+	}
+}
+
+impl std::error::Error for BuildError {}
+
+impl From<RuleError> for BuildError {
+	fn from(err: RuleError) -> Self {
+		Self::Rule(err)
+	}
+}
+
+impl From<BuildError> for JsValue {
+	fn from(err: BuildError) -> Self {
+		JsValue::from_str(&err.to_string())
+	}
+}
+
+/// Fluent construction of a universe.
+///
+/// Random cells are filled in before patterns are placed on top of them.
+#[derive(Clone, Debug, Default)]
+pub struct UniverseBuilder {
+	size: Option<(u32, u32)>,
+	rule: Option<String>,
+	boundary: Boundary,
+	layout: Layout,
+	patterns: Vec<(String, u32, u32)>,
+	density: Option<f64>,
+	seed: Option<u64>,
+}
+
+impl UniverseBuilder {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the dimensions of the universe, required.
+	pub fn size(mut self, width: u32, height: u32) -> Self {
+		self.size = Some((width, height));
+		self
+	}
+
+	/// Set the rule from a string like "B3/S23".
+	pub fn rule(mut self, rule: &str) -> Self {
+		self.rule = Some(rule.to_string());
+		self
+	}
+
+	pub fn boundary(mut self, boundary: Boundary) -> Self {
+		self.boundary = boundary;
+		self
+	}
+
+	pub fn layout(mut self, layout: Layout) -> Self {
+		self.layout = layout;
+		self
+	}
+
+	/// Place the named pattern offset by provided amount.
+	pub fn pattern(mut self, name: &str, dx: u32, dy: u32) -> Self {
+		self.patterns.push((name.to_string(), dx, dy));
+		self
+	}
+
+	/// Make each cell alive with provided probability.
+	pub fn random(mut self, density: f64) -> Self {
+		self.density = Some(density);
+		self
+	}
+
+	/// Seed the random fill to make it deterministic.
+	pub fn seed(mut self, seed: u64) -> Self {
+		self.seed = Some(seed);
+		self
+	}
+
+	pub fn build(self) -> Result<Universe, BuildError> {
+		let (width, height) = self.size.ok_or(BuildError::MissingSize)?;
+		let mut universe = Universe::empty_with_layout(width, height, self.layout);
+		if let Some(density) = self.density {
+			let mut rng = self.seed.map_or_else(Rng::from_entropy, Rng::new);
+			universe.fill_random(density, &mut rng);
+		}
+		if let Some(rule) = self.rule {
+			universe.set_rule(rule.parse::<Rule>()?);
+		}
+		universe.set_boundary(self.boundary);
+		for (name, dx, dy) in self.patterns {
+			let cells = shape::named_pattern(&name).ok_or(BuildError::UnknownPattern(name))?;
+			universe.place(cells.iter().copied(), dx, dy);
+		}
+		Ok(universe)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_build() {
+		let universe = UniverseBuilder::new()
+			.size(32, 24)
+			.rule("B36/S23")
+			.boundary(Boundary::Dead)
+			.random(0.3)
+			.seed(42)
+			.build()
+			.unwrap();
+
+		assert_eq!(universe, Universe::random_with_density_seeded(32, 24, 0.3, 42));
+		assert_eq!(universe.rule(), "B36/S23".parse().unwrap());
+
+		// without a seed the fill is random outside the browser too
+		let universe = UniverseBuilder::new().size(32, 24).random(1.0).build().unwrap();
+		assert_eq!(universe.population(), 32 * 24);
+	}
+
+	#[test]
+	fn test_build_pattern() {
+		let universe = UniverseBuilder::new().size(8, 8).pattern("block", 2, 3).build().unwrap();
+		let mut expected = Universe::empty(8, 8);
+		expected.place(shape::BLOCK.iter().copied(), 2, 3);
+		assert_eq!(universe, expected);
+	}
+
+	#[test]
+	fn test_build_errors() {
+		assert_eq!(UniverseBuilder::new().build().unwrap_err(), BuildError::MissingSize);
+		assert_eq!(
+			UniverseBuilder::new().size(4, 4).rule("nonsense").build().unwrap_err(),
+			BuildError::Rule(RuleError::Malformed),
+		);
+		assert_eq!(
+			UniverseBuilder::new().size(4, 4).pattern("spaceship", 0, 0).build().unwrap_err(),
+			BuildError::UnknownPattern("spaceship".to_string()),
+		);
+	}
+}
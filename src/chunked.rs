@@ -0,0 +1,190 @@
+//! Unbounded universe stored as chunks of cells that are only allocated around living ones.
+
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+
+use crate::bitstore::BitStore;
+use crate::rule::{Rule, RuleError};
+
+/// Width and height of a chunk in cells.
+const CHUNK_SIZE: i32 = 32;
+
+/// Get the coordinates of the chunk containing provided cell and the index of the cell within it.
+fn locate(x: i32, y: i32) -> ((i32, i32), usize) {
+	let chunk = (x.div_euclid(CHUNK_SIZE), y.div_euclid(CHUNK_SIZE));
+	let idx = y.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE + x.rem_euclid(CHUNK_SIZE);
+	(chunk, idx as usize)
+}
+
+/// Universe without edges that grows as its patterns expand.
+///
+/// Cells are stored in 32 by 32 chunks, chunks without living cells are dropped after each tick.
+/// Note: rules that birth cells without living neighbors would fill the infinite plane, so they are not supported.
+/// Cells are addressed by `i32` coordinates, cells that would be born past them are dropped.
+#[wasm_bindgen]
+#[derive(Clone, Debug, Default)]
+pub struct SparseUniverse {
+	/// Chunks of cells in row-major order keyed by chunk coordinates.
+	chunks: HashMap<(i32, i32), BitStore>,
+	rule: Rule,
+	generation: u64,
+}
+
+#[wasm_bindgen]
+impl SparseUniverse {
+	/// Create an empty universe evolving by Conway's rule.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the rule the universe evolves by from a string like "B3/S23".
+	///
+	/// Rules that birth cells without living neighbors are rejected.
+	pub fn set_rule_from_str(&mut self, rule: &str) -> Result<(), RuleError> {
+		let rule: Rule = rule.parse()?;
+		if rule.next_state(false, 0) {
+			return Err(RuleError::BirthWithoutNeighbors);
+		}
+		self.rule = rule;
+		Ok(())
+	}
+
+	/// Get the number of generations the universe has advanced since it was created.
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Test whether the cell at provided coordinates is alive.
+	pub fn get_cell(&self, x: i32, y: i32) -> bool {
+		let (chunk, idx) = locate(x, y);
+		self.chunks.get(&chunk).is_some_and(|cells| cells.get(idx))
+	}
+
+	/// Set the cell at provided coordinates to be alive or dead.
+	pub fn set_cell(&mut self, x: i32, y: i32, alive: bool) {
+		let (chunk, idx) = locate(x, y);
+		match self.chunks.get_mut(&chunk) {
+			Some(cells) => cells.set(idx, alive),
+			None if alive => {
+				let mut cells = BitStore::empty((CHUNK_SIZE * CHUNK_SIZE) as usize);
+				cells.set(idx, true);
+				self.chunks.insert(chunk, cells);
+			},
+			None => (),
+		}
+	}
+
+	/// Get the number of living cells in the universe.
+	pub fn population(&self) -> u32 {
+		self.chunks.values().map(|cells| cells.count_ones() as u32).sum()
+	}
+
+	/// Get the coordinates of living cells as packed x, y pairs in row-major order.
+	pub fn live_cells(&self) -> Vec<i32> {
+		let mut cells = self.live_coords();
+		cells.sort_unstable_by_key(|&(x, y)| (y, x));
+		cells.into_iter().flat_map(|(x, y)| [x, y]).collect()
+	}
+
+	/// Advance the universe by a single generation.
+	pub fn tick(&mut self) {
+		let live = self.live_coords();
+		let mut counts = HashMap::new();
+		for &(x, y) in &live {
+			// living cells without neighbors still need to be evaluated
+			counts.entry((x, y)).or_insert(0);
+			for dy in -1..=1 {
+				for dx in -1..=1 {
+					if dx == 0 && dy == 0 {
+						continue;
+					}
+					if let (Some(nx), Some(ny)) = (x.checked_add(dx), y.checked_add(dy)) {
+						*counts.entry((nx, ny)).or_insert(0) += 1;
+					}
+				}
+			}
+		}
+
+		let mut next = Self { chunks: HashMap::new(), rule: self.rule, generation: self.generation + 1 };
+		for ((x, y), count) in counts {
+			if self.rule.next_state(self.get_cell(x, y), count) {
+				next.set_cell(x, y, true);
+			}
+		}
+		*self = next;
+	}
+}
+
+impl SparseUniverse {
+	/// Get the coordinates of living cells in no particular order.
+	fn live_coords(&self) -> Vec<(i32, i32)> {
+		self.chunks.iter()
+			.flat_map(|(&(cx, cy), cells)| {
+				cells.iter_set_bits().map(move |idx| {
+					let idx = idx as i32;
+					(cx * CHUNK_SIZE + idx % CHUNK_SIZE, cy * CHUNK_SIZE + idx / CHUNK_SIZE)
+				})
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::shape;
+
+	#[test]
+	fn test_set_cell() {
+		let mut universe = SparseUniverse::new();
+		universe.set_cell(-1, 40, true);
+		universe.set_cell(3, -2, true);
+		universe.set_cell(5, 5, false);
+
+		assert!(universe.get_cell(-1, 40));
+		assert!(!universe.get_cell(31, 40));
+		assert_eq!(universe.population(), 2);
+		assert_eq!(universe.live_cells(), &[3, -2, -1, 40]);
+		assert_eq!(universe.chunks.len(), 2);
+	}
+
+	#[test]
+	fn test_glider_keeps_moving() {
+		let mut universe = SparseUniverse::new();
+		for &(x, y) in shape::GLIDER {
+			universe.set_cell(x as i32, y as i32, true);
+		}
+		let start = universe.live_cells();
+
+		for _ in 0..40 {
+			universe.tick();
+		}
+		// the glider moves a cell up and left every 4 generations without ever wrapping
+		let expected: Vec<_> = start.iter().map(|c| c - 10).collect();
+		assert_eq!(universe.live_cells(), expected);
+		assert_eq!(universe.generation(), 40);
+		assert_eq!(universe.chunks.len(), 1);
+	}
+
+	#[test]
+	fn test_tick_at_coordinate_limits() {
+		let mut universe = SparseUniverse::new();
+		for x in i32::MAX - 2..=i32::MAX {
+			universe.set_cell(x, i32::MIN, true);
+		}
+
+		// the blinker turns vertical, losing the cell above the smallest coordinate
+		universe.tick();
+		assert_eq!(universe.live_cells(), &[i32::MAX - 1, i32::MIN, i32::MAX - 1, i32::MIN + 1]);
+		universe.tick();
+		assert_eq!(universe.population(), 0);
+	}
+
+	#[test]
+	fn test_rejects_birth_without_neighbors() {
+		let mut universe = SparseUniverse::new();
+		assert_eq!(universe.set_rule_from_str("B0/S"), Err(RuleError::BirthWithoutNeighbors));
+		assert_eq!(universe.set_rule_from_str("B36/S23"), Ok(()));
+	}
+}
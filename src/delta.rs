@@ -0,0 +1,100 @@
+//! Compact encoding of the cells that changed between two states of a universe.
+//!
+//! The delta is a sequence of LEB128 varints of ascending row-major cell indices,
+//! each stored as the difference from the previous one.
+
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+/// Reasons a delta may fail to decode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeltaError {
+	/// The last varint is missing its final byte.
+	Truncated,
+	/// A varint or cell index does not fit in 64 bits.
+	Overflow,
+	/// A cell index is outside the universe.
+	IndexOutOfRange(u64),
+}
+
+impl fmt::Display for DeltaError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Truncated => write!(f, "delta ends in the middle of a cell index"),
+			Self::Overflow => write!(f, "delta cell index does not fit in 64 bits"),
+			Self::IndexOutOfRange(idx) => write!(f, "delta cell index {} is outside the universe", idx),
+		}
+	}
+}
+
+impl std::error::Error for DeltaError {}
+
+impl From<DeltaError> for JsValue {
+	fn from(err: DeltaError) -> Self {
+		JsValue::from_str(&err.to_string())
+	}
+}
+
+/// Encode provided ascending cell indices.
+pub fn encode<T>(indices: T) -> Vec<u8>
+where T: IntoIterator<Item = u64> {
+	let mut bytes = Vec::new();
+	let mut previous = 0;
+	for idx in indices {
+		let mut value = idx - previous;
+		previous = idx;
+		while value >= 0x80 {
+			bytes.push(value as u8 | 0x80);
+			value >>= 7;
+		}
+		bytes.push(value as u8);
+	}
+	bytes
+}
+
+/// Decode the cell indices of a delta.
+pub fn decode(bytes: &[u8]) -> Result<Vec<u64>, DeltaError> {
+	let mut indices = Vec::new();
+	let (mut value, mut shift) = (0u64, 0);
+	let mut previous = 0u64;
+	for &byte in bytes {
+		let bits = (byte & 0x7F) as u64;
+		if shift >= 64 || bits << shift >> shift != bits {
+			return Err(DeltaError::Overflow);
+		}
+		value |= bits << shift;
+		shift += 7;
+		if byte & 0x80 == 0 {
+			previous = previous.checked_add(value).ok_or(DeltaError::Overflow)?;
+			indices.push(previous);
+			value = 0;
+			shift = 0;
+		}
+	}
+	if shift > 0 {
+		return Err(DeltaError::Truncated);
+	}
+	Ok(indices)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_round_trip() {
+		let indices = [0, 5, 127, 128, 300, 1 << 40, u64::MAX];
+		let bytes = encode(indices);
+		assert_eq!(decode(&bytes), Ok(indices.to_vec()));
+		assert_eq!(&encode([3, 130]), &[3, 0x7F]);
+		assert!(decode(&[]).unwrap().is_empty());
+	}
+
+	#[test]
+	fn test_decode_errors() {
+		assert_eq!(decode(&[0x80]), Err(DeltaError::Truncated));
+		assert_eq!(decode(&[0xFF; 11]), Err(DeltaError::Overflow));
+		assert_eq!(decode(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 1]), Err(DeltaError::Overflow));
+	}
+}
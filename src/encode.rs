@@ -0,0 +1,77 @@
+//! Shared parts of image exports, enabled with the `png` and `gif` features.
+
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+/// Reasons a universe may fail to encode as an image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncodeError {
+	/// Cells need to be at least a single pixel.
+	ZeroScale,
+	/// The scaled image dimensions do not fit in 32 bits or the format.
+	TooLarge,
+	/// An animation needs at least a single frame.
+	NoFrames,
+	/// Frames of an animation have different dimensions.
+	FrameSizeMismatch,
+	/// The encoder failed with provided message.
+	Encoder(String),
+}
+
+impl fmt::Display for EncodeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::ZeroScale => write!(f, "image scale should be at least 1"),
+			Self::TooLarge => write!(f, "scaled image dimensions are too large"),
+			Self::NoFrames => write!(f, "animation has no frames"),
+			Self::FrameSizeMismatch => write!(f, "animation frames have different dimensions"),
+			Self::Encoder(message) => write!(f, "failed to encode image: {}", message),
+		}
+	}
+}
+
+impl std::error::Error for EncodeError {}
+
+impl From<EncodeError> for JsValue {
+	fn from(err: EncodeError) -> Self {
+		JsValue::from_str(&err.to_string())
+	}
+}
+
+/// Scale a row-major RGBA pixel buffer so each pixel becomes a `scale` by `scale` square.
+///
+/// Returns the scaled width, height and pixels.
+pub fn scale_rgba(pixels: &[u8], width: u32, height: u32, scale: u32) -> Result<(u32, u32, Vec<u8>), EncodeError> {
+	if scale == 0 {
+		return Err(EncodeError::ZeroScale);
+	}
+	let scaled_width = width.checked_mul(scale).ok_or(EncodeError::TooLarge)?;
+	let scaled_height = height.checked_mul(scale).ok_or(EncodeError::TooLarge)?;
+
+	let mut scaled = Vec::with_capacity(scaled_width as usize * scaled_height as usize * 4);
+	for y in 0..scaled_height {
+		for x in 0..scaled_width {
+			let idx = ((y / scale) * width + x / scale) as usize * 4;
+			scaled.extend_from_slice(&pixels[idx..idx + 4]);
+		}
+	}
+	Ok((scaled_width, scaled_height, scaled))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_scale_rgba() {
+		let pixels = [1, 1, 1, 1, 2, 2, 2, 2];
+		let (width, height, scaled) = scale_rgba(&pixels, 2, 1, 2).unwrap();
+
+		assert_eq!((width, height), (4, 2));
+		assert_eq!(scaled[..16], [1, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2]);
+		assert_eq!(scaled[..16], scaled[16..]);
+		assert_eq!(scale_rgba(&pixels, 2, 1, 0), Err(EncodeError::ZeroScale));
+		assert_eq!(scale_rgba(&pixels, 2, 1, u32::MAX), Err(EncodeError::TooLarge));
+	}
+}
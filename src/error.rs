@@ -0,0 +1,43 @@
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+/// Reasons an operation on a universe may fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UniverseError {
+	/// The universe has more cells than the operation can feasibly handle.
+	TooLarge { cells: u32, max_cells: u32 },
+	/// The universe would have no cells.
+	ZeroDimension { width: u32, height: u32 },
+	/// The number of kernel weights does not match its radius.
+	KernelSizeMismatch { expected: usize, actual: usize },
+	/// The operation only supports universes with 2 cell states.
+	TooManyStates { states: u8 },
+}
+
+impl fmt::Display for UniverseError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::TooLarge { cells, max_cells } => {
+				write!(f, "universe has {} cells, but at most {} are supported", cells, max_cells)
+			},
+			Self::ZeroDimension { width, height } => {
+				write!(f, "universe dimensions {}x{} must both be positive", width, height)
+			},
+			Self::KernelSizeMismatch { expected, actual } => {
+				write!(f, "kernel should have {} weights, but has {}", expected, actual)
+			},
+			Self::TooManyStates { states } => {
+				write!(f, "universe has {} cell states, but only 2 are supported", states)
+			},
+		}
+	}
+}
+
+impl std::error::Error for UniverseError {}
+
+impl From<UniverseError> for JsValue {
+	fn from(err: UniverseError) -> Self {
+		JsValue::from_str(&err.to_string())
+	}
+}
@@ -0,0 +1,113 @@
+//! Recording of animated GIFs, enabled with the `gif` feature.
+
+use std::convert::TryFrom;
+
+use wasm_bindgen::prelude::*;
+
+use crate::encode::{scale_rgba, EncodeError};
+use crate::Universe;
+
+impl From<gif::EncodingError> for EncodeError {
+	fn from(err: gif::EncodingError) -> Self {
+		Self::Encoder(err.to_string())
+	}
+}
+
+/// Recorder of universe generations to be encoded as an animation.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct FrameRecorder {
+	alive: u32,
+	dead: u32,
+	/// Width, height and RGBA pixels of each recorded frame.
+	frames: Vec<(u32, u32, Vec<u8>)>,
+}
+
+#[wasm_bindgen]
+impl FrameRecorder {
+	/// Create a recorder drawing cells with provided colors packed as `0xRRGGBBAA`.
+	pub fn new(alive: u32, dead: u32) -> Self {
+		Self { alive, dead, frames: Vec::new() }
+	}
+
+	/// Capture the current state of provided universe as the next frame.
+	pub fn record(&mut self, universe: &Universe) {
+		let pixels = universe.render_rgba(self.alive, self.dead);
+		self.frames.push((universe.width(), universe.height(), pixels));
+	}
+
+	/// Get the number of recorded frames.
+	pub fn frame_count(&self) -> usize {
+		self.frames.len()
+	}
+
+	/// Encode the recorded frames as a looping GIF with each cell drawn as a `scale` by `scale` square.
+	///
+	/// Note: GIF delays are in hundredths of a second, so `delay_ms` is rounded down to a multiple of 10.
+	pub fn encode_gif(self, scale: u32, delay_ms: u16) -> Result<Vec<u8>, EncodeError> {
+		let (width, height) = match self.frames.first() {
+			Some(&(width, height, _)) => (width, height),
+			None => return Err(EncodeError::NoFrames),
+		};
+		if self.frames.iter().any(|&(w, h, _)| (w, h) != (width, height)) {
+			return Err(EncodeError::FrameSizeMismatch);
+		}
+		if scale == 0 {
+			return Err(EncodeError::ZeroScale);
+		}
+
+		let scaled_width = u16::try_from(width.saturating_mul(scale)).map_err(|_| EncodeError::TooLarge)?;
+		let scaled_height = u16::try_from(height.saturating_mul(scale)).map_err(|_| EncodeError::TooLarge)?;
+		let mut encoder = gif::Encoder::new(Vec::new(), scaled_width, scaled_height, &[])?;
+		encoder.set_repeat(gif::Repeat::Infinite)?;
+		for (_, _, pixels) in &self.frames {
+			let (_, _, mut pixels) = scale_rgba(pixels, width, height, scale)?;
+			let mut frame = gif::Frame::from_rgba_speed(scaled_width, scaled_height, &mut pixels, 10);
+			frame.delay = delay_ms / 10;
+			encoder.write_frame(&frame)?;
+		}
+		Ok(encoder.into_inner()?)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::shape;
+
+	#[test]
+	fn test_encode_gif() {
+		let mut universe = Universe::empty(5, 5);
+		universe.place(shape::BLINKER.iter().copied(), 1, 2);
+		let mut recorder = FrameRecorder::new(0xFFFF_FFFF, 0x0000_00FF);
+		for _ in 0..3 {
+			recorder.record(&universe);
+			universe.tick();
+		}
+		let bytes = recorder.encode_gif(2, 100).unwrap();
+
+		let mut decoder = gif::DecodeOptions::new().read_info(bytes.as_slice()).unwrap();
+		assert_eq!((decoder.width(), decoder.height()), (10, 10));
+		let mut frames = 0;
+		while let Some(frame) = decoder.read_next_frame().unwrap() {
+			assert_eq!(frame.delay, 10);
+			frames += 1;
+		}
+		assert_eq!(frames, 3);
+	}
+
+	#[test]
+	fn test_encode_gif_errors() {
+		let mut recorder = FrameRecorder::new(0xFFFF_FFFF, 0x0000_00FF);
+		assert_eq!(recorder.clone().encode_gif(1, 0), Err(EncodeError::NoFrames));
+
+		recorder.record(&Universe::empty(4, 4));
+		recorder.record(&Universe::empty(4, 5));
+		assert_eq!(recorder.encode_gif(1, 0), Err(EncodeError::FrameSizeMismatch));
+
+		let mut recorder = FrameRecorder::new(0xFFFF_FFFF, 0x0000_00FF);
+		recorder.record(&Universe::empty(4, 4));
+		assert_eq!(recorder.clone().encode_gif(0, 0), Err(EncodeError::ZeroScale));
+		assert_eq!(recorder.encode_gif(1 << 15, 0), Err(EncodeError::TooLarge));
+	}
+}
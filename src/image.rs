@@ -0,0 +1,50 @@
+//! Import of universes from images, enabled with the `image` feature.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{DecodeError, Universe};
+
+#[wasm_bindgen]
+impl Universe {
+	/// Create a universe the size of the encoded image, with cells of pixels darker than `threshold` alive.
+	///
+	/// Pixels are converted to 8-bit luminance, so a threshold of 128 splits the range in half.
+	/// Supports PNG images.
+	pub fn from_image_bytes(bytes: &[u8], threshold: u8) -> Result<Universe, DecodeError> {
+		let image = image::load_from_memory(bytes).map_err(|_| DecodeError::InvalidImage)?.into_luma8();
+		let (width, height) = image.dimensions();
+		width.checked_mul(height).filter(|&len| len > 0).ok_or(DecodeError::InvalidDimensions)?;
+
+		Ok(Self::from_fn(width, height, |x, y| image.get_pixel(x, y).0[0] < threshold))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// A 4 by 2 grayscale PNG with black pixels at (0, 0) and (2, 0) and a dark gray one at (2, 1).
+	const IMAGE: [u8; 73] = [
+		0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+		0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02, 0x08, 0x00, 0x00, 0x00, 0x00, 0x5A, 0xC3, 0x22,
+		0xBF, 0x00, 0x00, 0x00, 0x10, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9C, 0x63, 0x60, 0xF8, 0x0F, 0x82,
+		0xFF, 0x53, 0xFE, 0x03, 0x00, 0x16, 0xBC, 0x05, 0x60, 0x8D, 0x52, 0x54, 0xF6, 0x00, 0x00, 0x00,
+		0x00, 0x49, 0x45, 0x4E, 0x44, 0xAE, 0x42, 0x60, 0x82,
+	];
+
+	#[test]
+	fn test_from_image_bytes() {
+		let universe = Universe::from_image_bytes(&IMAGE, 128).unwrap();
+		assert_eq!((universe.width(), universe.height()), (4, 2));
+		assert_eq!(universe.live_cells(), vec![0, 0, 2, 0, 2, 1]);
+
+		let universe = Universe::from_image_bytes(&IMAGE, 64).unwrap();
+		assert_eq!(universe.live_cells(), vec![0, 0, 2, 0]);
+	}
+
+	#[test]
+	fn test_from_image_bytes_invalid() {
+		assert_eq!(Universe::from_image_bytes(b"not an image", 128).err(), Some(DecodeError::InvalidImage));
+		assert_eq!(Universe::from_image_bytes(&IMAGE[..40], 128).err(), Some(DecodeError::InvalidImage));
+	}
+}
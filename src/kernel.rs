@@ -0,0 +1,47 @@
+use crate::UniverseError;
+
+/// Weights of the cells around a cell, summed to get its neighbor count.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Kernel {
+	radius: u32,
+	/// Row-major weights of the `(2 * radius + 1)` cells wide square centered on the cell.
+	weights: Vec<i32>,
+}
+
+impl Kernel {
+	/// Create a kernel, or an error if the number of weights does not match the radius.
+	pub fn new(radius: u32, weights: Vec<i32>) -> Result<Self, UniverseError> {
+		let side = radius as usize * 2 + 1;
+		if weights.len() != side * side {
+			return Err(UniverseError::KernelSizeMismatch { expected: side * side, actual: weights.len() });
+		}
+		Ok(Self { radius, weights })
+	}
+
+	/// Iterate over the offsets from the center and weights of cells with non-zero weight.
+	pub fn iter(&self) -> impl Iterator<Item = (i64, i64, i32)> + '_ {
+		let side = self.radius as usize * 2 + 1;
+		let radius = self.radius as i64;
+		self.weights.iter()
+			.enumerate()
+			.filter(|(_, &weight)| weight != 0)
+			.map(move |(i, &weight)| ((i % side) as i64 - radius, (i / side) as i64 - radius, weight))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_iter() {
+		let kernel = Kernel::new(1, vec![0, 2, 0, 0, 0, 0, 0, 0, -1]).unwrap();
+		assert_eq!(kernel.iter().collect::<Vec<_>>(), &[(0, -1, 2), (1, 1, -1)]);
+	}
+
+	#[test]
+	fn test_size_mismatch() {
+		assert_eq!(Kernel::new(2, vec![1; 9]), Err(UniverseError::KernelSizeMismatch { expected: 25, actual: 9 }));
+		assert!(Kernel::new(0, vec![1]).is_ok());
+	}
+}
@@ -0,0 +1,89 @@
+use wasm_bindgen::prelude::*;
+
+/// Side of the square blocks of cells of the tiled layout.
+const TILE_SIZE: u32 = 8;
+
+/// Order in which cells are stored in memory.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Layout {
+	/// Rows of cells one after another.
+	#[default]
+	RowMajor,
+	/// Rows of 8x8 blocks of cells, each block stored contiguously in row-major order.
+	///
+	/// Vertical neighbors are closer in memory, which is friendlier to the cache on large universes.
+	/// Note: blocks past the edges of the universe are padded with dead cells.
+	Tiled,
+}
+
+impl Layout {
+	/// Get the number of cells needed to store a universe of provided dimensions, including padding.
+	pub fn len(self, width: u32, height: u32) -> usize {
+		match self {
+			Self::RowMajor => (width * height) as usize,
+			Self::Tiled => {
+				let tiles = width.div_ceil(TILE_SIZE) * height.div_ceil(TILE_SIZE);
+				(tiles * TILE_SIZE * TILE_SIZE) as usize
+			},
+		}
+	}
+
+	/// Get the index of the cell at provided coordinates in a universe of provided width.
+	pub fn idx(self, x: u32, y: u32, width: u32) -> usize {
+		match self {
+			Self::RowMajor => (y * width + x) as usize,
+			Self::Tiled => {
+				let tile = y / TILE_SIZE * width.div_ceil(TILE_SIZE) + x / TILE_SIZE;
+				(tile * TILE_SIZE * TILE_SIZE + y % TILE_SIZE * TILE_SIZE + x % TILE_SIZE) as usize
+			},
+		}
+	}
+
+	/// Get the coordinates of the cell at provided index in a universe of provided width.
+	pub fn coords(self, idx: usize, width: u32) -> (u32, u32) {
+		let idx = idx as u32;
+		match self {
+			Self::RowMajor => (idx % width, idx / width),
+			Self::Tiled => {
+				let (tile, offset) = (idx / (TILE_SIZE * TILE_SIZE), idx % (TILE_SIZE * TILE_SIZE));
+				let tiles_x = width.div_ceil(TILE_SIZE);
+				(
+					tile % tiles_x * TILE_SIZE + offset % TILE_SIZE,
+					tile / tiles_x * TILE_SIZE + offset / TILE_SIZE,
+				)
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_tiled_len() {
+		assert_eq!(Layout::Tiled.len(8, 8), 64);
+		assert_eq!(Layout::Tiled.len(9, 8), 128);
+		assert_eq!(Layout::Tiled.len(20, 10), 384);
+		assert_eq!(Layout::RowMajor.len(20, 10), 200);
+	}
+
+	#[test]
+	fn test_coords_round_trip() {
+		for layout in [Layout::RowMajor, Layout::Tiled] {
+			let mut seen = vec![false; layout.len(13, 10)];
+			for y in 0..10 {
+				for x in 0..13 {
+					let idx = layout.idx(x, y, 13);
+					assert!(!seen[idx], "{:?} maps several cells to {}", layout, idx);
+					seen[idx] = true;
+					assert_eq!(layout.coords(idx, 13), (x, y));
+				}
+			}
+		}
+		assert_eq!(Layout::Tiled.idx(0, 1, 13), 8);
+		assert_eq!(Layout::Tiled.idx(8, 0, 13), 64);
+		assert_eq!(Layout::Tiled.idx(0, 8, 13), 128);
+	}
+}
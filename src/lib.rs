@@ -1,8 +1,57 @@
 mod utils;
 mod bitstore;
-mod shape;
+pub mod shape;
+mod rng;
+mod rule;
+mod boundary;
+mod neighborhood;
+mod kernel;
+mod pattern;
+mod layout;
+mod parallel;
+mod rle;
+mod life106;
+mod snapshot;
+mod delta;
+mod error;
+mod builder;
+mod chunked;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(any(feature = "png", feature = "gif"))]
+mod encode;
+#[cfg(feature = "png")]
+mod png;
+#[cfg(feature = "gif")]
+mod gif;
+#[cfg(feature = "image")]
+mod image;
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::convert::TryFrom;
 
 use bitstore::BitStore;
+pub use bitstore::BitOrder;
+use kernel::Kernel;
+use rng::Rng;
+pub use boundary::Boundary;
+pub use delta::DeltaError;
+pub use builder::{BuildError, UniverseBuilder};
+pub use chunked::SparseUniverse;
+pub use error::UniverseError;
+pub use layout::Layout;
+pub use neighborhood::Neighborhood;
+pub use pattern::Pattern;
+pub use rule::{Rule, RuleError};
+pub use rle::{parse_rle, RleError};
+pub use life106::parse_life106;
+pub use shape::{named_pattern, Transformation};
+pub use snapshot::DecodeError;
+#[cfg(any(feature = "png", feature = "gif"))]
+pub use encode::EncodeError;
+#[cfg(feature = "gif")]
+pub use crate::gif::FrameRecorder;
 use wasm_bindgen::prelude::*;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
@@ -11,183 +60,3609 @@ use wasm_bindgen::prelude::*;
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
+/// Get the distinct wrapping offsets to the neighbors along a dimension of provided size.
+///
+/// Note: in dimensions narrower than 3 cells several offsets would wrap onto the same cell.
+fn neighbor_offsets(size: u32) -> impl Iterator<Item = u32> {
+	IntoIterator::into_iter([size - 1, 0, 1]).take(size.min(3) as usize)
+}
+
+/// Add provided offset to a coordinate, wrapping around a dimension of provided size.
+///
+/// Note: unlike `wrapping_add` followed by modulus this is correct for any coordinate and offset.
+fn wrapping_offset(c: u32, offset: u32, size: u32) -> u32 {
+	((c as u64 + offset as u64) % size as u64) as u32
+}
+
+/// Check whether the neighbor at provided wrapping offset along a dimension is only reached across an edge.
+///
+/// Note: in dimensions narrower than 3 cells every neighbor is also reached directly.
+fn crosses_edge(c: u32, offset: u32, size: u32) -> bool {
+	size > 2 && (offset == 1 && c == size - 1 || offset == size - 1 && c == 0)
+}
+
+/// Get the current time in milliseconds with `Date.now()`.
+#[cfg(target_arch = "wasm32")]
+fn default_clock() -> f64 {
+	js_sys::Date::now()
+}
+
+/// Get the current time in milliseconds since the Unix epoch, `Date.now()` is only available in the browser.
+#[cfg(not(target_arch = "wasm32"))]
+fn default_clock() -> f64 {
+	std::time::SystemTime::now()
+		.duration_since(std::time::UNIX_EPOCH)
+		.map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0)
+}
+
+/// Number of set bits of each byte.
+const POPCOUNT_TABLE: [u8; 256] = {
+	let mut table = [0; 256];
+	let mut byte = 1;
+	while byte < 256 {
+		table[byte] = table[byte / 2] + (byte % 2) as u8;
+		byte += 1;
+	}
+	table
+};
+
+/// Number of generations `count_spaceships` waits for groups to move.
+///
+/// Common spaceships, including the glider and the lightweight, middleweight and heavyweight ones, have period 4.
+/// Note: over a single generation their cells change shape, so they would never match a translation.
+const SPACESHIP_PERIOD: u32 = 4;
+
+/// Maximum number of cells `predecessor_exists` will search through.
+const MAX_PREDECESSOR_SEARCH_CELLS: u32 = 16;
+
 #[wasm_bindgen]
-#[derive(Debug, PartialEq, Eq)]
 pub struct Universe {
 	width: u32,
 	height: u32,
 	cells: BitStore,
+	/// Back buffer the next generation is written into, swapped with `cells` every tick.
+	next: BitStore,
+	/// Cells that changed state during the last tick.
+	changed: BitStore,
+	rule: Rule,
+	boundary: Boundary,
+	neighborhood: Neighborhood,
+	/// Order of cells in all cell buffers.
+	layout: Layout,
+	generation: u64,
+	/// Previous generations, most recent last.
+	history: VecDeque<BitStore>,
+	/// Maximum number of previous generations kept, 0 disables history.
+	history_capacity: usize,
+	/// Populations after each tick, most recent last.
+	population_log: VecDeque<u32>,
+	/// Maximum number of populations logged, 0 disables the log.
+	population_log_capacity: usize,
+	/// Durations of recent ticks in milliseconds, most recent last.
+	tick_times: VecDeque<f64>,
+	/// Maximum number of tick durations kept, 0 disables timing.
+	tick_times_capacity: usize,
+	/// Current time in milliseconds, used to time ticks.
+	clock: fn() -> f64,
+	/// Whether to only evaluate cells near living ones during tick.
+	sparse: bool,
+	/// Scratch buffer of cells evaluated by a sparse tick.
+	active: BitStore,
+	/// Scratch buffers of the cells of each row packed into words, as is and shifted right and left.
+	///
+	/// Grown on the first bit-parallel tick, each holds an extra dead row after the last one.
+	rows: [Vec<u64>; 3],
+	/// Incremented whenever the cell buffers are reallocated.
+	cells_version: u64,
+	/// Number of generations each cell has stayed alive, empty unless age tracking is enabled.
+	ages: Vec<u8>,
+	/// Weights of cells summed to get neighbor counts instead of the neighborhood.
+	kernel: Option<Kernel>,
+	/// Number of cell states, more than 2 for rules of the Generations family.
+	states: u8,
+	/// States of cells that stopped being alive but are not dead yet, 0 for the rest.
+	///
+	/// Empty unless there are more than 2 states, living cells are always stored in `cells`.
+	dying: Vec<u8>,
+	/// Cells made alive after every tick regardless of the rule.
+	injection: Vec<(u32, u32)>,
 }
 
 // Private helper methods
 impl Universe {
+	/// Create a universe with provided cells and default settings.
+	fn from_cells(width: u32, height: u32, cells: BitStore) -> Self {
+		let len = cells.valid_bits();
+		Self {
+			width,
+			height,
+			cells,
+			next: BitStore::empty(len),
+			changed: BitStore::empty(len),
+			rule: Rule::default(),
+			boundary: Boundary::default(),
+			neighborhood: Neighborhood::default(),
+			layout: Layout::default(),
+			generation: 0,
+			history: VecDeque::new(),
+			history_capacity: 0,
+			population_log: VecDeque::new(),
+			population_log_capacity: 0,
+			tick_times: VecDeque::new(),
+			tick_times_capacity: 0,
+			clock: default_clock,
+			sparse: false,
+			active: BitStore::empty(len),
+			rows: Default::default(),
+			cells_version: 0,
+			ages: Vec::new(),
+			kernel: None,
+			states: 2,
+			dying: Vec::new(),
+			injection: Vec::new(),
+		}
+	}
+
+	/// Create an empty universe, treating dimensions of 0 as 1.
+	fn empty_clamped(width: u32, height: u32) -> Self {
+		Self::empty(width.max(1), height.max(1))
+	}
+
+	/// Create a universe with cells alive where provided function returns true.
+	///
+	/// Note: dimensions of 0 are treated as 1.
+	fn from_fn<F>(width: u32, height: u32, alive: F) -> Self
+	where F: Fn(u32, u32) -> bool {
+		let mut universe = Self::empty_clamped(width, height);
+		for y in 0..height {
+			for x in 0..width {
+				let idx = universe.idx(x, y);
+				universe.cells.set(idx, alive(x, y));
+			}
+		}
+		universe
+	}
+
 	/// Get the index of the cell at provided coordinates.
 	///
 	/// Note: emulates a wrapping universe by using modulus.
 	fn idx(&self, x: u32, y: u32) -> usize {
-		(y % self.height * self.width + x % self.width) as usize
+		self.layout.idx(x % self.width, y % self.height, self.width)
+	}
+
+	/// Get the coordinates of the cell at provided index.
+	fn coords(&self, idx: usize) -> (u32, u32) {
+		self.layout.coords(idx, self.width)
+	}
+
+	/// Get the number of cells in each cell buffer, including padding of the layout.
+	fn storage_len(&self) -> usize {
+		self.layout.len(self.width, self.height)
+	}
+
+	/// Get the coordinates of cells set in provided store as packed x, y pairs in row-major order.
+	fn packed_coords(&self, store: &BitStore) -> Vec<u32> {
+		let len = self.storage_len();
+		let mut coords: Vec<_> = store.iter_set_bits()
+			.take_while(|&idx| idx < len)
+			.map(|idx| self.coords(idx))
+			.collect();
+		if self.layout != Layout::RowMajor {
+			coords.sort_unstable_by_key(|&(x, y)| (y, x));
+		}
+		coords.into_iter().flat_map(|(x, y)| [x, y]).collect()
+	}
+
+	/// Get the cells in row-major order, copying them if the universe uses a different layout.
+	fn row_major_cells(&self) -> Cow<'_, BitStore> {
+		match self.layout {
+			Layout::RowMajor => Cow::Borrowed(&self.cells),
+			_ => {
+				let len = self.storage_len();
+				let mut cells = BitStore::empty((self.width * self.height) as usize);
+				for idx in self.cells.iter_set_bits().take_while(|&idx| idx < len) {
+					let (x, y) = self.coords(idx);
+					// padding of the layout holds no cells
+					if x < self.width && y < self.height {
+						cells.set(Layout::RowMajor.idx(x, y, self.width), true);
+					}
+				}
+				Cow::Owned(cells)
+			},
+		}
+	}
+
+	/// Replace the cells with a newly allocated store.
+	fn replace_cells(&mut self, cells: BitStore) {
+		self.cells = cells;
+		self.cells_version += 1;
+	}
+
+	/// Move every living cell to the position provided by the mapping.
+	fn remap<F>(&mut self, f: F)
+	where F: Fn(u32, u32) -> (u32, u32) {
+		let len = self.storage_len();
+		let mut cells = BitStore::empty(len);
+		for idx in self.cells.iter_set_bits().take_while(|&idx| idx < len) {
+			let (x, y) = self.coords(idx);
+			let (x, y) = f(x, y);
+			cells.set(self.idx(x, y), true);
+		}
+		self.replace_cells(cells);
+
+		if !self.dying.is_empty() {
+			let mut dying = vec![0; len];
+			for (idx, &state) in self.dying.iter().enumerate().filter(|(_, &state)| state > 0) {
+				let (x, y) = self.coords(idx);
+				let (x, y) = f(x, y);
+				dying[self.idx(x, y)] = state;
+			}
+			self.dying = dying;
+		}
+	}
+
+	/// Transform the cells of a square universe, returning whether it is square.
+	fn rotate(&mut self, t: Transformation) -> bool {
+		if self.width != self.height {
+			return false;
+		}
+		let (w, h) = (self.width, self.height);
+		self.remap(|x, y| shape::transform((x, y), w, h, t));
+		true
+	}
+
+	/// Check whether the neighbor at provided wrapping offset along a dimension is within the universe.
+	fn is_neighbor_inside(&self, c: u32, offset: u32, size: u32) -> bool {
+		match self.boundary {
+			Boundary::Toroidal => true,
+			Boundary::Dead => offset == 0
+				|| (offset == 1 && c + 1 < size)
+				|| (offset == size - 1 && c > 0),
+		}
 	}
 
 	/// Get the number of living neighbors around the provided cell.
 	fn live_neighbor_count(&self, x: u32, y: u32) -> u32 {
+		let radius = if self.neighborhood == Neighborhood::MooreRange2 { 2 } else { 1 };
+		if let Some(neighbors) = self.packed_neighbors(x, y, radius) {
+			// the orthogonal neighbors within the 3x3 square, without the cell itself
+			let mask = if self.neighborhood == Neighborhood::VonNeumann { 0b0101_1010 } else { u32::MAX };
+			return (neighbors & mask).to_le_bytes().iter().map(|&byte| POPCOUNT_TABLE[byte as usize] as u32).sum();
+		}
+		if self.neighborhood == Neighborhood::MooreRange2 {
+			return self.range2_neighbor_count(x, y);
+		}
+		let (xs, x_count) = self.neighbor_coords(x, self.prev_x(x), self.next_x(x), self.width);
+		let (ys, y_count) = self.neighbor_coords(y, self.prev_y(y), self.next_y(y), self.height);
 		let mut count = 0;
-		for yo in [self.height - 1, 0, 1] {
-			for xo in [self.width - 1, 0, 1] {
-				if xo == 0 && yo == 0 {
+		for &ny in &ys[..y_count] {
+			for &nx in &xs[..x_count] {
+				if nx == x && ny == y {
+					continue;
+				}
+				if self.neighborhood == Neighborhood::VonNeumann && nx != x && ny != y {
 					continue;
 				}
+				count += self.cells.get(self.idx(nx, ny)) as u32;
+			}
+		}
+
+		count
+	}
 
-				let idx = self.idx(x.wrapping_add(xo), y.wrapping_add(yo));
-				count += self.cells.get(idx) as u32;
+	/// Get the cells of the square within provided radius of a cell packed into bits row by row, leaving out the cell itself.
+	///
+	/// Each row of the square is read at once, so this is only available for row-major universes
+	/// where the square fits between the left and right edges and does not overlap itself vertically.
+	fn packed_neighbors(&self, x: u32, y: u32, radius: u32) -> Option<u32> {
+		let size = 2 * radius + 1;
+		if self.layout != Layout::RowMajor || x < radius || x + radius >= self.width || self.height < size {
+			return None;
+		}
+		let mut bits = 0;
+		for dy in 0..size {
+			let ny = y as i64 + dy as i64 - radius as i64;
+			if (0..self.height as i64).contains(&ny) || self.boundary == Boundary::Toroidal {
+				let ny = ny.rem_euclid(self.height as i64) as u32;
+				bits |= self.cells.get_bits(self.idx(x - radius, ny), size as usize) << (dy * size);
 			}
 		}
+		let center = radius * size + radius;
+		Some((bits & !(u64::MAX << center) | bits >> (center + 1) << center) as u32)
+	}
+
+	/// Get the column left of provided one, wrapping around the edge.
+	fn prev_x(&self, x: u32) -> u32 {
+		((x as u64 + self.width as u64 - 1) % self.width as u64) as u32
+	}
+
+	/// Get the column right of provided one, wrapping around the edge.
+	fn next_x(&self, x: u32) -> u32 {
+		((x as u64 + 1) % self.width as u64) as u32
+	}
+
+	/// Get the row above provided one, wrapping around the edge.
+	fn prev_y(&self, y: u32) -> u32 {
+		((y as u64 + self.height as u64 - 1) % self.height as u64) as u32
+	}
+
+	/// Get the row below provided one, wrapping around the edge.
+	fn next_y(&self, y: u32) -> u32 {
+		((y as u64 + 1) % self.height as u64) as u32
+	}
+
+	/// Get the distinct coordinates of a cell and its neighbors along a dimension, the cell's own first.
+	///
+	/// Returns the coordinates and how many of them are used, neighbors only reached across
+	/// a dead edge are left out and in dimensions narrower than 3 cells the neighbors coincide.
+	fn neighbor_coords(&self, c: u32, prev: u32, next: u32, size: u32) -> ([u32; 3], usize) {
+		let dead = self.boundary == Boundary::Dead;
+		let mut coords = [c; 3];
+		let mut count = 1;
+		if prev != c && !(dead && c == 0) {
+			coords[count] = prev;
+			count += 1;
+		}
+		if next != c && next != coords[count - 1] && !(dead && c + 1 == size) {
+			coords[count] = next;
+			count += 1;
+		}
+		(coords, count)
+	}
 
+	/// Get the number of living cells within 2 cells of the provided one in each direction.
+	///
+	/// Note: on universes narrower than 5 cells the same cell may be counted several times.
+	fn range2_neighbor_count(&self, x: u32, y: u32) -> u32 {
+		let (width, height) = (self.width as i64, self.height as i64);
+		let mut count = 0;
+		for dy in -2..=2 {
+			for dx in -2..=2 {
+				let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+				let inside = (0..width).contains(&nx) && (0..height).contains(&ny);
+				if dx == 0 && dy == 0 || !inside && self.boundary == Boundary::Dead {
+					continue;
+				}
+				count += self.cells.get(self.idx(nx.rem_euclid(width) as u32, ny.rem_euclid(height) as u32)) as u32;
+			}
+		}
 		count
 	}
 
-	/// Get the cells of the universe.
-	pub fn cells(&self) -> &BitStore {
-		&self.cells
+	/// Get the neighbor count of provided cell the rule is applied to.
+	///
+	/// Note: negative weighted sums are treated as too large to satisfy any rule.
+	fn rule_neighbor_count(&self, x: u32, y: u32) -> u32 {
+		match self.kernel {
+			Some(_) => u32::try_from(self.weighted_neighbor_sum(x, y)).unwrap_or(u32::MAX),
+			None => self.live_neighbor_count(x, y),
+		}
 	}
 
-	pub fn place<T>(&mut self, cells: T, xo: u32, yo: u32)
-	where T: IntoIterator<Item = (u32, u32)> {
-		for (x, y) in cells {
-			self.cells.set(self.idx(x.wrapping_add(xo), y.wrapping_add(yo)), true)
+	/// Write the next state of provided cell into the back buffer.
+	fn step_cell(&mut self, x: u32, y: u32) {
+		let idx = self.idx(x, y);
+		let neighbors = self.rule_neighbor_count(x, y);
+		let was_alive = self.cells.get(idx);
+		let alive = self.rule.next_state(was_alive, neighbors);
+		self.next.set(idx, alive);
+		self.changed.set(idx, alive ^ was_alive);
+	}
+
+	/// Write the next generation into the back buffer, evaluating every cell individually.
+	fn tick_dense(&mut self) {
+		// every cell of the back buffer is overwritten, so stale state does not leak
+		for y in 0..self.height {
+			for x in 0..self.width {
+				self.step_cell(x, y);
+			}
 		}
 	}
-}
 
-// Public methods
-#[wasm_bindgen]
-impl Universe {
-	/// Get the cell state at provided coordinates.
+	/// Write the cells of provided row packed into words, 64 cells per word.
+	fn row_words(&self, y: u32, words: &mut [u64]) {
+		words.iter_mut().for_each(|word| *word = 0);
+		for x in 0..self.width {
+			words[x as usize / 64] |= (self.cells.get(self.idx(x, y)) as u64) << (x % 64);
+		}
+	}
+
+	/// Call provided function with each word of each row and the same word of its 8 neighboring rows.
 	///
-	/// True means 'alive', false means 'dead'.
-	pub fn tick(&mut self) {
-		let mut next = self.cells.clone();
-	
+	/// The neighboring rows are shifted so bit `x` of each holds a neighbor of cell `x`,
+	/// the first 4 are the orthogonal neighbors, followed by the diagonal ones.
+	/// Note: only correct for universes that are at least 3 cells in each dimension.
+	fn for_each_row_word<F>(&mut self, mut f: F)
+	where F: FnMut(&mut Self, u32, usize, u64, [u64; 8]) {
+		let wrap = self.boundary == Boundary::Toroidal;
+		let words = (self.width as usize).div_ceil(64);
+		// the buffers are reused across ticks, only allocating when the universe grows
+		let [mut rows, mut right, mut left] = std::mem::take(&mut self.rows);
+		for buffer in [&mut rows, &mut right, &mut left] {
+			buffer.clear();
+			buffer.resize((self.height as usize + 1) * words, 0);
+		}
+		for y in 0..self.height as usize {
+			let range = y * words..(y + 1) * words;
+			self.row_words(y as u32, &mut rows[range.clone()]);
+			parallel::shift_right(&rows[range.clone()], self.width, wrap, &mut right[range.clone()]);
+			parallel::shift_left(&rows[range.clone()], self.width, wrap, &mut left[range]);
+		}
+
+		let dead = self.height;
+		for y in 0..self.height {
+			let above = if wrap || y > 0 { wrapping_offset(y, self.height - 1, self.height) } else { dead };
+			let below = if wrap || y + 1 < self.height { wrapping_offset(y, 1, self.height) } else { dead };
+			let (row, above, below) = (y as usize * words, above as usize * words, below as usize * words);
+
+			for word in 0..words {
+				let neighbors = [
+					rows[above + word], rows[below + word], right[row + word], left[row + word],
+					right[above + word], left[above + word], right[below + word], left[below + word],
+				];
+				f(self, y, word, rows[row + word], neighbors);
+			}
+		}
+		self.rows = [rows, right, left];
+	}
+
+	/// Write the next states of the cells of provided row word into the back buffer.
+	fn write_row_word(&mut self, y: u32, word: usize, cells: u64, next: u64) {
+		let start = word as u32 * 64;
+		if self.layout == Layout::RowMajor {
+			let (idx, len) = (self.idx(start, y), (self.width - start).min(64) as usize);
+			self.next.set_bits(idx, len, next);
+			self.changed.set_bits(idx, len, next ^ cells);
+			return;
+		}
+		for x in start..self.width.min(start + 64) {
+			let idx = self.idx(x, y);
+			let alive = next >> (x % 64) & 1 == 1;
+			self.next.set(idx, alive);
+			self.changed.set(idx, alive ^ (cells >> (x % 64) & 1 == 1));
+		}
+	}
+
+	/// Write the next generation into the back buffer, evaluating 64 cells of a row at once.
+	///
+	/// Neighbor counts are summed from copies of the neighboring rows shifted left and right.
+	/// Note: only correct for universes that are at least 3 cells in each dimension.
+	fn tick_parallel(&mut self) {
+		let count = if self.neighborhood == Neighborhood::Moore { 8 } else { 4 };
+		self.for_each_row_word(|universe, y, word, cells, neighbors| {
+			let mut counter = parallel::Counter::default();
+			for &neighbors in &neighbors[..count] {
+				counter.add(neighbors);
+			}
+			let next = counter.matching(universe.rule.birth) & !cells | counter.matching(universe.rule.survival) & cells;
+			universe.write_row_word(y, word, cells, next);
+		});
+	}
+
+	/// Write the next generation of a universe with more than 2 states into the back buffer.
+	///
+	/// Living cells that do not survive start dying, going through the remaining states before they are dead.
+	/// Only dead cells may be born and only living cells count as neighbors.
+	fn tick_generations(&mut self) {
+		let mut dying = std::mem::take(&mut self.dying);
 		for y in 0..self.height {
 			for x in 0..self.width {
 				let idx = self.idx(x, y);
-				let live_neighbor_count = self.live_neighbor_count(x, y);
+				let was_alive = self.cells.get(idx);
+				let neighbors = self.rule_neighbor_count(x, y);
+				let (alive, state) = match (was_alive, dying[idx]) {
+					(true, _) if self.rule.next_state(true, neighbors) => (true, 0),
+					(true, _) => (false, 2),
+					(false, 0) => (self.rule.next_state(false, neighbors), 0),
+					(false, state) => (false, if state + 1 < self.states { state + 1 } else { 0 }),
+				};
+				dying[idx] = state;
+				self.next.set(idx, alive);
+				self.changed.set(idx, alive ^ was_alive);
+			}
+		}
+		self.dying = dying;
+	}
+
+	/// Run provided function on the universe, then restore the universe to its state before it.
+	///
+	/// The function is passed the original cells, history is disabled while it runs.
+	fn simulate<T, F>(&mut self, f: F) -> T
+	where F: FnOnce(&mut Self, &BitStore) -> T {
+		let original = self.cells.clone();
+		let changed = self.changed.clone();
+		let generation = self.generation;
+		let ages = self.ages.clone();
+		let dying = self.dying.clone();
+		let history = std::mem::take(&mut self.history);
+		let history_capacity = std::mem::replace(&mut self.history_capacity, 0);
+		let population_log_capacity = std::mem::replace(&mut self.population_log_capacity, 0);
+		let tick_times_capacity = std::mem::replace(&mut self.tick_times_capacity, 0);
+
+		let result = f(self, &original);
+
+		self.replace_cells(original);
+		self.changed = changed;
+		self.generation = generation;
+		self.ages = ages;
+		self.dying = dying;
+		self.history = history;
+		self.history_capacity = history_capacity;
+		self.population_log_capacity = population_log_capacity;
+		self.tick_times_capacity = tick_times_capacity;
+		result
+	}
+
+	/// Split the living cells into universes of the same size, one for each group of touching cells.
+	fn component_universes(&self) -> Vec<Universe> {
+		let cells = self.live_cells();
+		let components = self.connected_components();
+		let count = components.iter().max().map_or(0, |&max| max + 1);
+		(0..count)
+			.map(|component| {
+				let members: Vec<_> = cells.chunks_exact(2)
+					.zip(&components)
+					.filter(|(_, &c)| c == component)
+					.flat_map(|(xy, _)| xy.iter().copied())
+					.collect();
+				Universe::from_coords(self.width, self.height, &members)
+			})
+			.collect()
+	}
+
+	/// Extract provided cells into a pattern of their bounding box.
+	///
+	/// Note: the bounding box may wrap around the edges of the universe if that makes it smaller.
+	fn extract(&self, cells: &[(u32, u32)]) -> Pattern {
+		/// Get the start and length of the shortest wrapping span containing provided coordinates.
+		fn span<I>(coords: I, size: u32) -> (u32, u32)
+		where I: Iterator<Item = u32> {
+			let mut coords: Vec<_> = coords.collect();
+			coords.sort_unstable();
+			coords.dedup();
+			// the span starts after the largest gap between consecutive coordinates
+			let (after_gap, gap) = (0..coords.len())
+				.map(|i| {
+					let next = coords[(i + 1) % coords.len()];
+					((i + 1) % coords.len(), (next + size - coords[i] - 1) % size + 1)
+				})
+				.max_by_key(|&(_, gap)| gap)
+				.unwrap_or((0, size));
+			(coords.get(after_gap).copied().unwrap_or(0), size - gap + 1)
+		}
+
+		let (x0, w) = span(cells.iter().map(|&(x, _)| x), self.width);
+		let (y0, h) = span(cells.iter().map(|&(_, y)| y), self.height);
+		let mut members = BitStore::empty(self.storage_len());
+		for &(x, y) in cells {
+			members.set(self.idx(x, y), true);
+		}
+		Pattern::from_fn(w, h, |x, y| {
+			members.get(self.idx(wrapping_offset(x0, x, self.width), wrapping_offset(y0, y, self.height)))
+		})
+	}
+
+	/// Advance the universe by a single generation, writing the back buffer with provided function.
+	fn advance<F>(&mut self, step: F)
+	where F: FnOnce(&mut Self) {
+		let start = if self.tick_times_capacity > 0 { (self.clock)() } else { 0.0 };
+		if self.history_capacity > 0 {
+			if self.history.len() == self.history_capacity {
+				self.history.pop_front();
+			}
+			self.history.push_back(self.cells.clone());
+		}
+
+		step(self);
+
+		std::mem::swap(&mut self.cells, &mut self.next);
+		self.generation += 1;
+		self.inject();
+		self.age_cells();
+
+		if self.tick_times_capacity > 0 {
+			if self.tick_times.len() == self.tick_times_capacity {
+				self.tick_times.pop_front();
+			}
+			self.tick_times.push_back((self.clock)() - start);
+		}
+		if self.population_log_capacity > 0 {
+			if self.population_log.len() == self.population_log_capacity {
+				self.population_log.pop_front();
+			}
+			self.population_log.push_back(self.population());
+		}
+	}
+
+	/// Make the injected cells alive, overriding the rule.
+	fn inject(&mut self) {
+		for i in 0..self.injection.len() {
+			let (x, y) = self.injection[i];
+			let idx = self.idx(x, y);
+			self.cells.set(idx, true);
+			// the back buffer holds the previous generation
+			self.changed.set(idx, !self.next.get(idx));
+			if let Some(state) = self.dying.get_mut(idx) {
+				*state = 0;
+			}
+		}
+	}
 
-				match (self.cells.get(idx), live_neighbor_count) {
-					(true, x) if x < 2 => next.set(idx, false),
-					(true, x) if x > 3 => next.set(idx, false),
-					(false, 3) => next.set(idx, true),
-					_ => (), // in other cases cell state remains the same
+	/// Write the next generation into the back buffer, only evaluating living cells and their neighbors.
+	///
+	/// Note: only correct for rules where dead cells without living neighbors stay dead.
+	fn tick_sparse(&mut self) {
+		let mut active = std::mem::replace(&mut self.active, BitStore::empty(0));
+		active.clear();
+		let len = self.storage_len();
+		for idx in self.cells.iter_set_bits().take_while(|&idx| idx < len) {
+			let (x, y) = self.coords(idx);
+			for yo in neighbor_offsets(self.height) {
+				for xo in neighbor_offsets(self.width) {
+					active.set(self.idx(wrapping_offset(x, xo, self.width), wrapping_offset(y, yo, self.height)), true);
 				}
 			}
 		}
 
-		self.cells = next;
+		self.next.clear();
+		self.changed.clear();
+		for idx in active.iter_set_bits() {
+			let (x, y) = self.coords(idx);
+			self.step_cell(x, y);
+		}
+		self.active = active;
 	}
 
-	/// Create an empty universe.
-	pub fn empty(width: u32, height: u32) -> Self {
-		Self {
-			width,
-			height,
-			cells: BitStore::empty((width*height) as usize)
+	/// Age cells that stayed alive during the last tick and reset the age of the rest.
+	fn age_cells(&mut self) {
+		for (idx, age) in self.ages.iter_mut().enumerate() {
+			*age = match (self.next.get(idx), self.cells.get(idx)) {
+				(true, true) => age.saturating_add(1),
+				_ => 0,
+			};
 		}
 	}
 
-	/// Initialize a new universe with an interesting pattern.
-	pub fn random(width: u32, height: u32) -> Self {
-        Self {
-            width,
-            height,
-            cells: BitStore::random((width*height) as usize),
-        }
+	/// Set every cell in provided region to a state produced by provided function.
+	///
+	/// Note: the region wraps around the edges of the universe.
+	fn fill_region_with<F>(&mut self, x0: u32, y0: u32, w: u32, h: u32, mut state: F)
+	where F: FnMut() -> bool {
+		for y in 0..h {
+			for x in 0..w {
+				let idx = self.idx(wrapping_offset(x0, x, self.width), wrapping_offset(y0, y, self.height));
+				self.cells.set(idx, state());
+			}
+		}
 	}
 
-    /// Get the width of the universe in cells.
-    pub fn width(&self) -> u32 {
-        self.width
-    }
+	/// Create a universe where each cell is alive with provided probability.
+	fn random_density(width: u32, height: u32, density: f64, rng: &mut Rng) -> Self {
+		let mut universe = Self::empty_clamped(width, height);
+		universe.fill_random(density, rng);
+		universe
+	}
 
-    /// Get the height of the universe in cells.
-    pub fn height(&self) -> u32 {
-        self.height
-    }
+	/// Create a universe where each cell at least `margin` cells away from every edge is alive with provided probability.
+	fn random_interior_with(width: u32, height: u32, margin: u32, density: f64, rng: &mut Rng) -> Self {
+		let mut universe = Self::empty_clamped(width, height);
+		let density = density.clamp(0.0, 1.0);
+		let (w, h) = (width.saturating_sub(margin.saturating_mul(2)), height.saturating_sub(margin.saturating_mul(2)));
+		universe.fill_region_with(margin, margin, w, h, || rng.next_f64() < density);
+		universe
+	}
 
-    /// Get the pointer to cell data in the universe.
-    pub fn cells_ptr(&self) -> *const u8 {
-        self.cells.as_ptr()
-    }
+	/// Make each cell alive with provided probability.
+	fn fill_random(&mut self, density: f64, rng: &mut Rng) {
+		let density = density.clamp(0.0, 1.0);
+		self.fill_region_with(0, 0, self.width, self.height, || rng.next_f64() < density);
+	}
 
-	pub fn cells_size(&self) -> usize {
-		self.cells.size()
+	/// Fill provided region with random cells from provided generator.
+	pub fn randomize_region_with(&mut self, x0: u32, y0: u32, w: u32, h: u32, rng: &mut Rng) {
+		self.fill_region_with(x0, y0, w, h, || rng.next_u64() >> 63 == 1);
 	}
 
-	/// Toggle provided cell.
-	pub fn toggle(&mut self, x: u32, y: u32) {
-		let idx = self.idx(x, y);
-		self.cells.set(idx, !self.cells.get(idx));
+	/// Get the cells of the universe.
+	///
+	/// Note: the cells are stored in the layout of the universe.
+	pub fn cells(&self) -> &BitStore {
+		&self.cells
 	}
 
-	/// Spawn a randomly transformed glider at provided coordinates.
-	pub fn spawn_glider(&mut self, x: u32, y: u32) {
-		let tr = shape::Transformation::random();
-		let cells = shape::GLIDER.iter()
-			.map(|cell| shape::transform(*cell, 3, 3, tr));
-		self.place(cells, x - 1, y - 1);
+	/// Get the rule the universe evolves by.
+	pub fn rule(&self) -> Rule {
+		self.rule
 	}
-}
 
+	/// Set the rule the universe evolves by.
+	pub fn set_rule(&mut self, rule: Rule) {
+		self.rule = rule;
+	}
 
-impl std::fmt::Display for Universe {
-	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-		for y in 0..self.height {
-			for x in 0..self.width {
-				if self.cells.get(self.idx(x, y)) {
-					write!(f, "X")?;
-				} else {
-					write!(f, "-")?;
-				}
-			}
-			write!(f, "\n")?;
+	/// Make provided cells offset by provided amount alive.
+	pub fn place<T>(&mut self, cells: T, xo: u32, yo: u32)
+	where T: IntoIterator<Item = (u32, u32)> {
+		for (x, y) in cells {
+			let idx = self.idx(wrapping_offset(x, xo, self.width), wrapping_offset(y, yo, self.height));
+			self.cells.set(idx, true)
 		}
+	}
 
-		Ok(())
+	/// Make provided cells offset by provided amount dead, the opposite of `place`.
+	///
+	/// Note: coordinates outside the universe wrap around.
+	pub fn subtract(&mut self, cells: &[(u32, u32)], dx: u32, dy: u32) {
+		for &(x, y) in cells {
+			let idx = self.idx(wrapping_offset(x, dx, self.width), wrapping_offset(y, dy, self.height));
+			self.cells.set(idx, false)
+		}
 	}
-}
 
-#[cfg(test)]
-mod test {
-	use super::*;
-	
-	#[test]
-	fn test_neighbor_count() {
-		// leave an empty row and column to avoid wrapping artifacts
-		let mut universe = Universe::empty(4, 4);
-		universe.place([(1, 0), (1, 1), (1, 2)], 0, 0);
+	/// Make provided cells offset by provided amount alive, dropping the ones outside the universe unless wrapping.
+	///
+	/// Returns the number of dropped cells, so 0 means the whole pattern fit.
+	/// Note: this only concerns placement, the boundary the universe evolves with is unaffected.
+	pub fn stamp(&mut self, cells: &[(u32, u32)], dx: u32, dy: u32, wrap: bool) -> usize {
+		if wrap {
+			self.place(cells.iter().copied(), dx, dy);
+			return 0;
+		}
+		let (width, height) = (self.width as u64, self.height as u64);
+		let mut dropped = 0;
+		for &(x, y) in cells {
+			let (x, y) = (x as u64 + dx as u64, y as u64 + dy as u64);
+			if x < width && y < height {
+				let idx = self.idx(x as u32, y as u32);
+				self.cells.set(idx, true);
+			} else {
+				dropped += 1;
+			}
+		}
+		dropped
+	}
 
-		assert_eq!(universe.live_neighbor_count(0, 0), 2);
-		assert_eq!(universe.live_neighbor_count(1, 0), 1);
-		assert_eq!(universe.live_neighbor_count(2, 0), 2);
-		
-		assert_eq!(universe.live_neighbor_count(0, 1), 3);
-		assert_eq!(universe.live_neighbor_count(1, 1), 2);
-		assert_eq!(universe.live_neighbor_count(2, 1), 3);
-		
-		assert_eq!(universe.live_neighbor_count(0, 2), 2);
-		assert_eq!(universe.live_neighbor_count(1, 2), 1);
-		assert_eq!(universe.live_neighbor_count(2, 2), 2);
+	/// Encode the cells that differ from provided previous state of the universe, see `apply_delta`.
+	///
+	/// Panics if the previous cells are of a different size.
+	pub fn delta_since(&self, previous: &BitStore) -> Vec<u8> {
+		let len = self.storage_len();
+		let mut indices: Vec<_> = self.cells.xor(previous)
+			.iter_set_bits()
+			.take_while(|&idx| idx < len)
+			.map(|idx| {
+				let (x, y) = self.coords(idx);
+				y as u64 * self.width as u64 + x as u64
+			})
+			.collect();
+		if self.layout != Layout::RowMajor {
+			indices.sort_unstable();
+		}
+		delta::encode(indices)
+	}
 
-		// check wrapping neighbors
-		assert_eq!(universe.live_neighbor_count(1, 3), 2);
+	/// Advance the universe by up to provided number of generations, calling provided function after each.
+	///
+	/// Stops early once the function returns false, returns the number of generations advanced.
+	pub fn run<F>(&mut self, max_steps: u32, mut on_step: F) -> u32
+	where F: FnMut(&Universe) -> bool {
+		for step in 1..=max_steps {
+			self.tick();
+			if !on_step(self) {
+				return step;
+			}
+		}
+		max_steps
 	}
 
-	#[test]
-	fn test_get_cell() {
-		const CELLS: &[(u32, u32)] = &[(1,2), (2,3), (3,1), (3,2), (3,3)];
-		let mut universe = Universe::empty(6, 6);
-		universe.place(CELLS.iter().copied(), 0, 0);
+	/// Advance the universe by one generation, computing it into the provided scratch buffer.
+	///
+	/// The scratch buffer must have the same length as the cells, afterwards it holds the previous generation.
+	pub fn tick_into(&mut self, scratch: &mut BitStore) {
+		debug_assert_eq!(scratch.valid_bits(), self.cells.valid_bits());
+		std::mem::swap(&mut self.next, scratch);
+		self.tick();
+		std::mem::swap(&mut self.next, scratch);
+	}
 
-		for &(x, y) in CELLS {
+	/// Crop the universe to the bounding box of living cells, returning the removed top-left offset.
+	///
+	/// An empty universe becomes a single dead cell with no offset.
+	/// Note: the bounding box does not wrap around the edges and the history is discarded.
+	pub fn shrink_to_fit(&mut self) -> (u32, u32) {
+		let live = self.live_cells();
+		if live.is_empty() {
+			self.resize(1, 1);
+			return (0, 0);
+		}
+		let (xs, ys) = (live.iter().step_by(2), live.iter().skip(1).step_by(2));
+		let (min_x, max_x) = (*xs.clone().min().unwrap(), *xs.max().unwrap());
+		let (min_y, max_y) = (*ys.clone().min().unwrap(), *ys.max().unwrap());
+
+		let (width, height) = (self.width, self.height);
+		self.remap(|x, y| (wrapping_offset(x, width - min_x, width), wrapping_offset(y, height - min_y, height)));
+		self.resize(max_x - min_x + 1, max_y - min_y + 1);
+		(min_x, min_y)
+	}
+
+	/// Replace the function providing the current time in milliseconds used to time ticks.
+	///
+	/// Defaults to `Date.now()` in the browser and the system time elsewhere.
+	pub fn set_clock(&mut self, clock: fn() -> f64) {
+		self.clock = clock;
+	}
+
+	/// Get the offset that moves the living cells of this universe onto the ones of the other, wrapping around the edges.
+	///
+	/// Returns `None` if no single translation aligns them or the universes have different dimensions.
+	/// Offsets are the shortest way around, so a glider moving up and left has a negative offset.
+	pub fn matches_translated(&self, other: &Universe) -> Option<(i32, i32)> {
+		if self.width != other.width || self.height != other.height || self.population() != other.population() {
+			return None;
+		}
+		let (width, height) = (self.width, self.height);
+		let live: Vec<_> = self.live_cells().chunks_exact(2).map(|xy| (xy[0], xy[1])).collect();
+		let (x0, y0) = match live.first() {
+			Some(&cell) => cell,
+			None => return Some((0, 0)),
+		};
+		/// Get the shortest signed offset equivalent to provided wrapping one.
+		fn signed(offset: u32, size: u32) -> i32 {
+			if offset > size / 2 {
+				(offset as i64 - size as i64) as i32
+			} else {
+				offset as i32
+			}
+		}
+
+		// the first living cell has to move onto one of the living cells of the other universe
+		other.live_cells()
+			.chunks_exact(2)
+			.map(|xy| (wrapping_offset(xy[0], width - x0, width), wrapping_offset(xy[1], height - y0, height)))
+			.find(|&(dx, dy)| {
+				live.iter().all(|&(x, y)| other.get_cell(wrapping_offset(x, dx, width), wrapping_offset(y, dy, height)))
+			})
+			.map(|(dx, dy)| (signed(dx, width), signed(dy, height)))
+	}
+
+	/// Make provided cells alive after every tick, regardless of the rule.
+	///
+	/// Replaces any previous injection, coordinates outside the universe wrap around.
+	pub fn set_injection(&mut self, coords: Vec<(u32, u32)>) {
+		self.injection = coords;
+	}
+
+	/// Stop making cells alive after every tick, see `set_injection`.
+	pub fn clear_injection(&mut self) {
+		self.injection.clear();
+	}
+
+	/// Make provided cells of a pattern with given dimensions alive after transforming them.
+	pub fn place_transformed<T>(&mut self, cells: T, xo: u32, yo: u32, w: u32, h: u32, t: Transformation)
+	where T: IntoIterator<Item = (u32, u32)> {
+		let cells = cells.into_iter().map(|cell| shape::transform(cell, w, h, t));
+		self.place(cells, xo, yo);
+	}
+
+	/// Get the average position of live cells, or `None` if there are none.
+	///
+	/// Coordinates are averaged on a circle, so a pattern straddling an edge has its center near that edge.
+	pub fn center_of_mass(&self) -> Option<(f64, f64)> {
+		fn circular_mean<I>(coords: I, size: u32) -> f64
+		where I: Iterator<Item = u32> {
+			let scale = std::f64::consts::TAU / size as f64;
+			let (sin, cos) = coords
+				.map(|c| c as f64 * scale)
+				.fold((0.0, 0.0), |(sin, cos), angle| (sin + angle.sin(), cos + angle.cos()));
+			sin.atan2(cos).rem_euclid(std::f64::consts::TAU) / scale
+		}
+
+		let len = self.storage_len();
+		let cells: Vec<_> = self.cells.iter_set_bits()
+			.take_while(|&idx| idx < len)
+			.map(|idx| self.coords(idx))
+			.collect();
+		if cells.is_empty() {
+			return None;
+		}
+		let x = circular_mean(cells.iter().map(|&(x, _)| x), self.width);
+		let y = circular_mean(cells.iter().map(|&(_, y)| y), self.height);
+		Some((x, y))
+	}
+}
+
+// Public methods
+#[wasm_bindgen]
+impl Universe {
+	/// Advance the universe by a single generation.
+	pub fn tick(&mut self) {
+		self.advance(|universe| {
+			if universe.states > 2 {
+				universe.tick_generations();
+				return;
+			}
+			// kernels and larger neighborhoods may reach further than the cells evaluated by the faster paths
+			if universe.kernel.is_some() || universe.neighborhood == Neighborhood::MooreRange2 {
+				universe.tick_dense();
+			// isolated dead cells only stay dead if the rule does not birth cells without neighbors
+			} else if universe.sparse && !universe.rule.next_state(false, 0) {
+				universe.tick_sparse();
+			} else if universe.width >= 3 && universe.height >= 3 {
+				universe.tick_parallel();
+			} else {
+				universe.tick_dense();
+			}
+		});
+	}
+
+	/// Advance the universe by a single generation, returning whether any cell changed state.
+	pub fn tick_checked(&mut self) -> bool {
+		// dying cells change state every generation
+		let dying = self.dying.iter().any(|&state| state > 0);
+		self.tick();
+		dying || self.changed.iter_set_bits().next().is_some()
+	}
+
+	/// Advance the universe by a single generation, evaluating each cell individually.
+	///
+	/// Slower than `tick` with identical results, kept for comparison.
+	pub fn tick_naive(&mut self) {
+		self.advance(Self::tick_dense);
+	}
+
+	/// Keep up to provided number of previous generations to allow stepping back.
+	///
+	/// Oldest generations are discarded once the capacity is reached, 0 disables history.
+	pub fn enable_history(&mut self, capacity: usize) {
+		self.history_capacity = capacity;
+		while self.history.len() > capacity {
+			self.history.pop_front();
+		}
+	}
+
+	/// Log up to provided number of populations, one after each tick.
+	///
+	/// Oldest populations are discarded once the capacity is reached, 0 disables the log.
+	pub fn enable_population_log(&mut self, capacity: usize) {
+		self.population_log_capacity = capacity;
+		while self.population_log.len() > capacity {
+			self.population_log.pop_front();
+		}
+	}
+
+	/// Get the logged populations, oldest first.
+	pub fn population_history(&self) -> Vec<u32> {
+		self.population_log.iter().copied().collect()
+	}
+
+	/// Time up to provided number of most recent ticks, see `average_tick_ms`.
+	///
+	/// Oldest durations are discarded once the window is full, 0 disables timing.
+	pub fn enable_timing(&mut self, window: usize) {
+		self.tick_times_capacity = window;
+		while self.tick_times.len() > window {
+			self.tick_times.pop_front();
+		}
+	}
+
+	/// Get the average duration of the timed ticks in milliseconds, or 0 if none were timed.
+	pub fn average_tick_ms(&self) -> f64 {
+		if self.tick_times.is_empty() {
+			return 0.0;
+		}
+		self.tick_times.iter().sum::<f64>() / self.tick_times.len() as f64
+	}
+
+	/// Restore the previous generation from history.
+	///
+	/// Returns false if there is no history to restore.
+	pub fn step_back(&mut self) -> bool {
+		match self.history.pop_back() {
+			Some(cells) => {
+				self.replace_cells(cells);
+				self.changed.clear();
+				// dying states are not part of the history
+				self.dying.iter_mut().for_each(|state| *state = 0);
+				self.generation = self.generation.saturating_sub(1);
+				true
+			},
+			None => false,
+		}
+	}
+
+	/// Advance the universe by provided number of generations.
+	pub fn tick_many(&mut self, steps: u32) {
+		for _ in 0..steps {
+			self.tick();
+		}
+	}
+
+	/// Find the smallest number of generations, up to provided maximum, after which the cells repeat.
+	///
+	/// Returns 1 for still lifes and `None` if the cells do not repeat in time.
+	/// The universe is restored to its original state before returning.
+	pub fn detect_period(&mut self, max_period: u32) -> Option<u32> {
+		self.simulate(|universe, original| (1..=max_period).find(|_| {
+			universe.tick();
+			universe.cells == *original
+		}))
+	}
+
+	/// Find the still lifes on the board, groups of touching living cells that do not change.
+	///
+	/// Each still life is extracted into a pattern of its bounding box, in `connected_components` order.
+	/// The universe is restored to its original state before returning.
+	pub fn still_lifes(&mut self) -> Vec<Pattern> {
+		let cells: Vec<_> = self.live_cells().chunks_exact(2).map(|xy| (xy[0], xy[1])).collect();
+		let components = self.connected_components();
+		let changed = self.simulate(|universe, _| {
+			universe.tick();
+			universe.changed.clone()
+		});
+
+		// a component is only still if neither its cells nor their neighbors changed
+		let count = components.iter().max().map_or(0, |&max| max as usize + 1);
+		let mut still = vec![true; count];
+		for (&(x, y), &component) in cells.iter().zip(&components) {
+			let touches_change = neighbor_offsets(self.height).any(|yo| neighbor_offsets(self.width).any(|xo| {
+				changed.get(self.idx(wrapping_offset(x, xo, self.width), wrapping_offset(y, yo, self.height)))
+			}));
+			if touches_change {
+				still[component as usize] = false;
+			}
+		}
+
+		(0..count as u32)
+			.filter(|&component| still[component as usize])
+			.map(|component| {
+				let members: Vec<_> = cells.iter()
+					.zip(&components)
+					.filter(|(_, &c)| c == component)
+					.map(|(&cell, _)| cell)
+					.collect();
+				self.extract(&members)
+			})
+			.collect()
+	}
+
+	/// Count the groups of touching living cells that move, see `SPACESHIP_PERIOD`.
+	///
+	/// A group is a spaceship if the same group appears translated after the period,
+	/// by at most a cell per generation in each direction.
+	/// The universe is restored to its original state before returning.
+	pub fn count_spaceships(&mut self) -> u32 {
+		let mut before = self.component_universes();
+		let mut after = self.simulate(|universe, _| {
+			universe.tick_many(SPACESHIP_PERIOD);
+			universe.component_universes()
+		});
+
+		// groups that reappear in place are not spaceships, even if an identical group is nearby
+		before.retain(|component| {
+			match after.iter().position(|other| component.matches_translated(other) == Some((0, 0))) {
+				Some(i) => {
+					after.swap_remove(i);
+					false
+				},
+				None => true,
+			}
+		});
+
+		let reach = SPACESHIP_PERIOD as i32;
+		let mut count = 0;
+		for component in &before {
+			let matched = after.iter().position(|other| {
+				component.matches_translated(other)
+					.is_some_and(|(dx, dy)| dx.abs() <= reach && dy.abs() <= reach)
+			});
+			if let Some(i) = matched {
+				// each moved group may only be matched once
+				after.swap_remove(i);
+				count += 1;
+			}
+		}
+		count
+	}
+
+	/// Check whether the next generation would be the same as the current one, without advancing.
+	///
+	/// Injected cells are alive in the next generation regardless of the rule, as after `tick`.
+	pub fn is_stable(&self) -> bool {
+		let mut injected = BitStore::empty(self.storage_len());
+		for &(x, y) in &self.injection {
+			injected.set(self.idx(x, y), true);
+		}
+		self.dying.iter().all(|&state| state == 0) && (0..self.height).all(|y| (0..self.width).all(|x| {
+			let idx = self.idx(x, y);
+			let alive = self.cells.get(idx);
+			let next = injected.get(idx) || self.rule.next_state(alive, self.rule_neighbor_count(x, y));
+			next == alive
+		}))
+	}
+
+	/// Check whether the cells repeat within provided number of generations.
+	///
+	/// The universe is restored to its original state before returning.
+	pub fn is_stable_within(&mut self, period: u32) -> bool {
+		self.detect_period(period).is_some()
+	}
+
+	/// Create an empty universe.
+	///
+	/// Panics if either dimension is 0, see `try_empty` for a checked version.
+	pub fn empty(width: u32, height: u32) -> Self {
+		Self::try_empty(width, height).expect("universe dimensions should be positive")
+	}
+
+	/// Create an empty universe, or an error if either dimension is 0.
+	pub fn try_empty(width: u32, height: u32) -> Result<Universe, UniverseError> {
+		if width == 0 || height == 0 {
+			return Err(UniverseError::ZeroDimension { width, height });
+		}
+		Ok(Self::from_cells(width, height, BitStore::empty((width*height) as usize)))
+	}
+
+	/// Create a universe with cells at provided packed x, y pairs alive.
+	///
+	/// Note: coordinates outside the universe wrap around, a trailing unpaired value is ignored.
+	/// Dimensions of 0 are treated as 1.
+	pub fn from_coords(width: u32, height: u32, coords: &[u32]) -> Universe {
+		let mut universe = Self::empty_clamped(width, height);
+		universe.set_cells_packed(coords, true);
+		universe
+	}
+
+	/// Create an empty universe evolving by a rule of the Generations family, like "B2/S" with 3 states for Brian's Brain.
+	///
+	/// Cells that stop being alive go through `states - 2` dying states before they are dead.
+	/// Note: a trailing `/` after the survival counts is allowed and dimensions of 0 are treated as 1.
+	pub fn with_generations_rule(width: u32, height: u32, states: u8, rule: &str) -> Result<Universe, RuleError> {
+		if states < 2 {
+			return Err(RuleError::TooFewStates(states));
+		}
+		let rule = rule.strip_suffix('/').unwrap_or(rule).parse()?;
+		let mut universe = Self::empty_clamped(width, height);
+		universe.rule = rule;
+		universe.states = states;
+		if states > 2 {
+			universe.dying = vec![0; universe.storage_len()];
+		}
+		Ok(universe)
+	}
+
+	/// Create an empty universe that stores its cells in provided layout.
+	///
+	/// The layout does not affect the simulation, only its performance and the memory at `cells_ptr`.
+	/// Note: dimensions of 0 are treated as 1.
+	pub fn empty_with_layout(width: u32, height: u32, layout: Layout) -> Self {
+		let (width, height) = (width.max(1), height.max(1));
+		let mut universe = Self::from_cells(width, height, BitStore::empty(layout.len(width, height)));
+		universe.layout = layout;
+		universe
+	}
+
+	/// Initialize a new universe with an interesting pattern.
+	///
+	/// Note: dimensions of 0 are treated as 1.
+	pub fn random(width: u32, height: u32) -> Self {
+		let (width, height) = (width.max(1), height.max(1));
+		Self::from_cells(width, height, BitStore::random((width*height) as usize))
+	}
+
+	/// Initialize a new universe deterministically from provided seed.
+	///
+	/// Universes created with the same seed and dimensions are identical.
+	/// Note: dimensions of 0 are treated as 1.
+	pub fn random_seeded(width: u32, height: u32, seed: u64) -> Self {
+		let (width, height) = (width.max(1), height.max(1));
+		Self::from_cells(width, height, BitStore::random_seeded((width*height) as usize, seed))
+	}
+
+	/// Create a universe sized to fit the provided RLE pattern.
+	///
+	/// Patterns without living cells have no size, so they result in `RleError::Universe`.
+	pub fn from_rle(rle: &str) -> Result<Universe, RleError> {
+		let (cells, width, height) = parse_rle(rle)?;
+		let mut universe = Self::try_empty(width, height)?;
+		universe.place(cells, 0, 0);
+		Ok(universe)
+	}
+
+	/// Make cells of provided RLE pattern offset by provided amount alive.
+	///
+	/// Note: cells that are already alive stay alive and coordinates wrap around.
+	pub fn insert_rle(&mut self, rle: &str, dx: u32, dy: u32) -> Result<(), RleError> {
+		let (cells, _, _) = parse_rle(rle)?;
+		self.place(cells, dx, dy);
+		Ok(())
+	}
+
+	/// Create a universe from lines of characters, where provided character marks living cells.
+	///
+	/// The width is that of the longest line, shorter lines are padded with dead cells.
+	/// Note: an empty string results in a single dead cell.
+	pub fn from_string(s: &str, alive: char) -> Universe {
+		let width = s.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+		let height = s.lines().count();
+		let mut universe = Self::empty_clamped(width as u32, height as u32);
+		for (y, line) in s.lines().enumerate() {
+			for (x, c) in line.chars().enumerate() {
+				if c == alive {
+					universe.set_cell(x as u32, y as u32, true);
+				}
+			}
+		}
+		universe
+	}
+
+	/// Encode the cells of the universe as an RLE pattern.
+	pub fn to_rle(&self) -> String {
+		rle::encode_rle(self.width, self.height, &self.rule, |x, y| self.get_cell(x, y))
+	}
+
+	/// Create a universe with the cells of provided Life 1.06 pattern.
+	///
+	/// The pattern is offset so its top-left corner is at the origin, cells that do not fit wrap around.
+	/// Note: dimensions of 0 are treated as 1.
+	pub fn from_life106(life106: &str, width: u32, height: u32) -> Universe {
+		let cells = parse_life106(life106);
+		let min_x = cells.iter().map(|&(x, _)| x).min().unwrap_or(0);
+		let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or(0);
+		let mut universe = Self::empty_clamped(width, height);
+		let (width, height) = (universe.width, universe.height);
+		universe.place(cells.into_iter().map(|(x, y)| (
+			(x.wrapping_sub(min_x) as u64 % width as u64) as u32,
+			(y.wrapping_sub(min_y) as u64 % height as u64) as u32,
+		)), 0, 0);
+		universe
+	}
+
+	/// Encode the live cells of the universe as a Life 1.06 pattern.
+	pub fn to_life106(&self) -> String {
+		let len = self.storage_len();
+		life106::encode_life106(self.cells.iter_set_bits()
+			.take_while(|&idx| idx < len)
+			.map(|idx| self.coords(idx)))
+	}
+
+	/// Encode the universe into a compact binary snapshot.
+	pub fn serialize(&self) -> Vec<u8> {
+		snapshot::encode(self.width, self.height, self.generation, self.row_major_cells().as_bytes())
+	}
+
+	/// Restore a universe from a binary snapshot created with `serialize`.
+	pub fn deserialize(bytes: &[u8]) -> Result<Universe, DecodeError> {
+		let snapshot = snapshot::decode(bytes)?;
+		let mut universe = Self::from_bytes(snapshot.width, snapshot.height, snapshot.cells.to_vec())?;
+		universe.generation = snapshot.generation;
+		Ok(universe)
+	}
+
+	/// Initialize a new random universe deterministically derived from provided label.
+	///
+	/// The same label always yields the same soup, which makes soups easy to share and reproduce.
+	/// Note: dimensions of 0 are treated as 1.
+	pub fn soup(width: u32, height: u32, label: &str) -> Self {
+		let (width, height) = (width.max(1), height.max(1));
+		let len = (width*height) as usize;
+		Self::from_cells(width, height, BitStore::random_with(len, &mut Rng::from_label(label)))
+	}
+
+	/// Create a universe from packed cell bytes, as returned by `cells_ptr`.
+	///
+	/// The number of bytes must be exactly enough to fit `width * height` cells.
+	pub fn from_bytes(width: u32, height: u32, bytes: Vec<u8>) -> Result<Universe, DecodeError> {
+		Self::from_bytes_with_order(width, height, bytes, BitOrder::LsbFirst)
+	}
+
+	/// Create a universe from row-major packed cell bytes with bits in provided order, for example from a PBM image.
+	///
+	/// The number of bytes must be exactly enough to fit `width * height` cells.
+	pub fn from_bytes_with_order(width: u32, height: u32, bytes: Vec<u8>, order: BitOrder) -> Result<Universe, DecodeError> {
+		let len = width.checked_mul(height)
+			.filter(|&len| len > 0)
+			.ok_or(DecodeError::InvalidDimensions)? as usize;
+		if bytes.len() != len.div_ceil(8) {
+			return Err(DecodeError::LengthMismatch { expected: len.div_ceil(8), actual: bytes.len() });
+		}
+
+		let mut cells = BitStore::from_bytes_with_order(bytes, order);
+		cells.trim_padding(len);
+		Ok(Self::from_cells(width, height, cells))
+	}
+
+	/// Check whether any state of the universe advances into the current one.
+	///
+	/// Uses a brute-force search, so only supports universes of up to 16 cells (4x4) with 2 cell states.
+	/// Respects the configured rule, boundary, neighborhood, kernel and injected cells.
+	pub fn predecessor_exists(&self) -> Result<bool, UniverseError> {
+		let cells = self.width * self.height;
+		if cells > MAX_PREDECESSOR_SEARCH_CELLS {
+			return Err(UniverseError::TooLarge { cells, max_cells: MAX_PREDECESSOR_SEARCH_CELLS });
+		}
+		if self.states > 2 {
+			return Err(UniverseError::TooManyStates { states: self.states });
+		}
+
+		let mut candidate = Self::empty_with_layout(self.width, self.height, self.layout);
+		candidate.rule = self.rule;
+		candidate.boundary = self.boundary;
+		candidate.neighborhood = self.neighborhood;
+		candidate.kernel = self.kernel.clone();
+		candidate.injection = self.injection.clone();
+		let target = self.row_major_cells();
+		for state in 0..1u32 << cells {
+			for y in 0..self.height {
+				for x in 0..self.width {
+					candidate.set_cell(x, y, state >> (y * self.width + x) & 1 == 1);
+				}
+			}
+			candidate.tick();
+			if *candidate.row_major_cells() == *target {
+				return Ok(true);
+			}
+		}
+		Ok(false)
+	}
+
+	/// Flip the cells listed in a delta created with `delta_since`.
+	///
+	/// No cells are changed if the delta is invalid.
+	pub fn apply_delta(&mut self, delta: &[u8]) -> Result<(), DeltaError> {
+		let len = self.width as u64 * self.height as u64;
+		let indices = delta::decode(delta)?;
+		if let Some(&idx) = indices.iter().find(|&&idx| idx >= len) {
+			return Err(DeltaError::IndexOutOfRange(idx));
+		}
+		for idx in indices {
+			self.toggle_cell((idx % self.width as u64) as u32, (idx / self.width as u64) as u32);
+		}
+		Ok(())
+	}
+
+	/// Set the behavior of the universe at its edges.
+	pub fn set_boundary(&mut self, boundary: Boundary) {
+		self.boundary = boundary;
+	}
+
+	/// Get the name of the behavior at the edges, "toroidal" or "dead".
+	pub fn boundary_mode(&self) -> String {
+		self.boundary.to_string()
+	}
+
+	/// Only evaluate living cells and their neighbors during tick.
+	///
+	/// Faster for mostly empty universes, the results are identical either way.
+	pub fn set_sparse(&mut self, sparse: bool) {
+		self.sparse = sparse;
+	}
+
+	/// Set the cells considered to be neighbors.
+	pub fn set_neighborhood(&mut self, neighborhood: Neighborhood) {
+		self.neighborhood = neighborhood;
+	}
+
+	/// Get the name of the cells considered neighbors, "moore", "von-neumann" or "moore-range2".
+	pub fn neighborhood(&self) -> String {
+		self.neighborhood.to_string()
+	}
+
+	/// Initialize a new universe where each cell is alive with provided probability.
+	///
+	/// Note: the density is clamped to the [0, 1] range and dimensions of 0 are treated as 1.
+	pub fn random_with_density(width: u32, height: u32, density: f64) -> Self {
+		Self::random_density(width, height, density, &mut Rng::from_entropy())
+	}
+
+	/// Initialize a new universe where each cell is alive with provided probability deterministically.
+	///
+	/// Note: the density is clamped to the [0, 1] range and dimensions of 0 are treated as 1.
+	pub fn random_with_density_seeded(width: u32, height: u32, density: f64, seed: u64) -> Self {
+		Self::random_density(width, height, density, &mut Rng::new(seed))
+	}
+
+	/// Initialize a new universe with random cells only at least `margin` cells away from every edge.
+	///
+	/// The dead margin keeps the soup from interacting across the edges right away.
+	/// Note: the density is clamped to the [0, 1] range, a margin of half the size or more leaves the universe empty.
+	/// Dimensions of 0 are treated as 1.
+	pub fn random_interior(width: u32, height: u32, margin: u32, density: f64) -> Self {
+		Self::random_interior_with(width, height, margin, density, &mut Rng::from_entropy())
+	}
+
+	/// Same as `random_interior`, but deterministic for provided seed.
+	pub fn random_interior_seeded(width: u32, height: u32, margin: u32, density: f64, seed: u64) -> Self {
+		Self::random_interior_with(width, height, margin, density, &mut Rng::new(seed))
+	}
+
+	/// Create a universe with a Gosper glider gun near the top-left corner.
+	///
+	/// The gun emits a glider towards the bottom-right every 30 generations,
+	/// so the universe should be considerably larger than the 36x9 gun.
+	/// Note: dimensions of 0 are treated as 1.
+	pub fn glider_gun(width: u32, height: u32) -> Self {
+		let mut universe = Self::empty_clamped(width, height);
+		universe.place(shape::GLIDER_GUN.iter().copied(), 1, 1);
+		universe
+	}
+
+	/// Create a universe with cells alive where the sum of their coordinates is even.
+	///
+	/// Note: dimensions of 0 are treated as 1.
+	pub fn checkerboard(width: u32, height: u32) -> Self {
+		Self::from_fn(width, height, |x, y| (x + y).is_multiple_of(2))
+	}
+
+	/// Create a universe of alternating alive and dead stripes of columns of provided width.
+	///
+	/// Note: a period or dimensions of 0 are treated as 1.
+	pub fn stripes(width: u32, height: u32, period: u32) -> Self {
+		let period = period.max(1);
+		Self::from_fn(width, height, |x, _| (x / period).is_multiple_of(2))
+	}
+
+	/// Set the rule the universe evolves by from a string like "B3/S23".
+	///
+	/// With the range 2 neighborhood rules in the Larger than Life notation like "R2,C0,M0,S7..12,B9..11,NM" are accepted too.
+	pub fn set_rule_from_str(&mut self, rule: &str) -> Result<(), RuleError> {
+		let range = rule.trim_start().starts_with(['R', 'r']);
+		self.rule = if range && self.neighborhood == Neighborhood::MooreRange2 {
+			Rule::parse_range(rule)?
+		} else {
+			rule.parse()?
+		};
+		Ok(())
+	}
+
+	/// Set the rule to a well-known one by name, for example `"highlife"`.
+	///
+	/// Recognizes "conway", "highlife", "daynight", "seeds", "replicator" and "life-without-death".
+	pub fn set_named_rule(&mut self, name: &str) -> Result<(), RuleError> {
+		self.rule = Rule::from_name(name)?;
+		Ok(())
+	}
+
+	/// Get the rule the universe evolves by in the canonical form, for example "B36/S23".
+	pub fn rule_string(&self) -> String {
+		self.rule.to_string()
+	}
+
+    /// Get the width of the universe in cells.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Get the height of the universe in cells.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+	/// Get the number of generations the universe has advanced since it was created.
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+
+    /// Get the pointer to cell data in the universe.
+    ///
+    /// Note: the cells are stored in the layout of the universe.
+    pub fn cells_ptr(&self) -> *const u8 {
+        self.cells.as_ptr()
+    }
+
+	pub fn cells_size(&self) -> usize {
+		self.cells.size()
+	}
+
+	/// Track the number of generations each cell has stayed alive, or stop tracking and free the ages.
+	///
+	/// Ages start at 0 for every cell and saturate at 255.
+	pub fn enable_ages(&mut self, enabled: bool) {
+		self.ages = if enabled {
+			vec![0; self.storage_len()]
+		} else {
+			Vec::new()
+		};
+	}
+
+	/// Get the pointer to the cell ages, one byte per cell.
+	///
+	/// Note: the ages are stored in the layout of the universe, including its padding,
+	/// and are reallocated by `enable_ages` and `resize`.
+	pub fn ages_ptr(&self) -> *const u8 {
+		self.ages.as_ptr()
+	}
+
+	/// Get the number of tracked ages, 0 if age tracking is disabled.
+	pub fn ages_size(&self) -> usize {
+		self.ages.len()
+	}
+
+	/// Get the version of the cell buffers, which changes whenever they are reallocated.
+	///
+	/// Views of wasm memory at `cells_ptr` must be recreated when the version changes.
+	/// Note: ticking swaps between two buffers without reallocating, so `cells_ptr` should still be read after every tick.
+	pub fn cells_version(&self) -> u64 {
+		self.cells_version
+	}
+
+	/// Get a copy of the packed cell bits in row-major order.
+	///
+	/// Unlike reading memory at `cells_ptr`, the copy stays valid if wasm memory grows, at the cost of an allocation.
+	pub fn cells_snapshot(&self) -> Vec<u8> {
+		self.row_major_cells().as_bytes().to_vec()
+	}
+
+	/// Render the universe into a row-major RGBA pixel buffer suitable for `ImageData`.
+	///
+	/// The colors are packed as `0xRRGGBBAA`.
+	pub fn render_rgba(&self, alive: u32, dead: u32) -> Vec<u8> {
+		let (alive, dead) = (alive.to_be_bytes(), dead.to_be_bytes());
+		let mut pixels = Vec::with_capacity((self.width * self.height * 4) as usize);
+		for y in 0..self.height {
+			for x in 0..self.width {
+				pixels.extend_from_slice(if self.cells.get(self.idx(x, y)) { &alive } else { &dead });
+			}
+		}
+		pixels
+	}
+
+	/// Render the changes of the last tick into a row-major RGBA pixel buffer suitable for `ImageData`.
+	///
+	/// Cells are colored by whether they were born, died, stayed alive or stayed dead during the last tick.
+	/// The colors are packed as `0xRRGGBBAA`.
+	pub fn render_activity(&self, born: u32, died: u32, stable_alive: u32, dead: u32) -> Vec<u8> {
+		let colors = [dead.to_be_bytes(), stable_alive.to_be_bytes(), died.to_be_bytes(), born.to_be_bytes()];
+		let mut pixels = Vec::with_capacity((self.width * self.height * 4) as usize);
+		for y in 0..self.height {
+			for x in 0..self.width {
+				let idx = self.idx(x, y);
+				let bucket = (self.changed.get(idx) as usize) << 1 | self.cells.get(idx) as usize;
+				pixels.extend_from_slice(&colors[bucket]);
+			}
+		}
+		pixels
+	}
+
+	/// Render the universe as lines of provided characters, each line ending with a newline.
+	pub fn render_string(&self, alive: char, dead: char) -> String {
+		let mut result = String::with_capacity(((self.width + 1) * self.height) as usize);
+		for y in 0..self.height {
+			for x in 0..self.width {
+				result.push(if self.cells.get(self.idx(x, y)) { alive } else { dead });
+			}
+			result.push('\n');
+		}
+		result
+	}
+
+	/// Get a hash of the dimensions and cells of the universe.
+	///
+	/// Uses FNV-1a, so equal universes hash the same on every platform, run and version.
+	pub fn state_hash(&self) -> u64 {
+		let cells = self.row_major_cells();
+		let len = (self.width * self.height) as usize;
+		let bytes = cells.as_bytes()[..len.div_ceil(8)].iter().enumerate().map(|(i, &byte)| {
+			// ignore the padding bits past the last cell
+			let valid = (len - i * 8).min(8);
+			byte & (0xFF >> (8 - valid))
+		});
+		utils::fnv1a(IntoIterator::into_iter(self.width.to_le_bytes()).chain(self.height.to_le_bytes()).chain(bytes))
+	}
+
+	/// Get the number of living cells in the universe.
+	pub fn population(&self) -> u32 {
+		let len = self.storage_len();
+		// the last byte may have padding bits that are not part of the universe
+		let padding = (len..self.cells.size() * 8)
+			.filter(|&idx| self.cells.get(idx))
+			.count();
+		(self.cells.count_ones() - padding) as u32
+	}
+
+	/// Get the coordinates of living cells as packed x, y pairs in row-major order.
+	pub fn live_cells(&self) -> Vec<u32> {
+		self.packed_coords(&self.cells)
+	}
+
+	/// Get the coordinates of cells that changed state during the last tick as packed x, y pairs.
+	pub fn changed_cells(&self) -> Vec<u32> {
+		self.packed_coords(&self.changed)
+	}
+
+	/// Get the number of living cells in provided region.
+	///
+	/// Note: like other regions the region wraps around the edges of the universe,
+	/// its size is clamped to that of the universe so no cell is counted twice.
+	pub fn population_in(&self, x0: u32, y0: u32, w: u32, h: u32) -> u32 {
+		let mut count = 0;
+		for y in 0..h.min(self.height) {
+			for x in 0..w.min(self.width) {
+				count += self.get_cell(wrapping_offset(x0, x, self.width), wrapping_offset(y0, y, self.height)) as u32;
+			}
+		}
+		count
+	}
+
+	/// Label groups of living cells that touch, including diagonally.
+	///
+	/// Returns a component id for each cell in `live_cells` order, ids are numbered in order of first appearance.
+	/// Note: cells touch across edges unless the boundary is dead.
+	pub fn connected_components(&self) -> Vec<u32> {
+		fn find(parents: &mut [usize], mut i: usize) -> usize {
+			while parents[i] != i {
+				parents[i] = parents[parents[i]];
+				i = parents[i];
+			}
+			i
+		}
+
+		let cells: Vec<_> = self.live_cells().chunks_exact(2).map(|xy| (xy[0], xy[1])).collect();
+		let mut positions = vec![usize::MAX; self.storage_len()];
+		for (i, &(x, y)) in cells.iter().enumerate() {
+			positions[self.idx(x, y)] = i;
+		}
+
+		let mut parents: Vec<_> = (0..cells.len()).collect();
+		for (i, &(x, y)) in cells.iter().enumerate() {
+			for yo in neighbor_offsets(self.height).filter(|&yo| self.is_neighbor_inside(y, yo, self.height)) {
+				for xo in neighbor_offsets(self.width).filter(|&xo| self.is_neighbor_inside(x, xo, self.width)) {
+					let neighbor = positions[self.idx(wrapping_offset(x, xo, self.width), wrapping_offset(y, yo, self.height))];
+					if neighbor != usize::MAX {
+						let (a, b) = (find(&mut parents, i), find(&mut parents, neighbor));
+						parents[a.max(b)] = a.min(b);
+					}
+				}
+			}
+		}
+
+		let mut ids = vec![u32::MAX; cells.len()];
+		let mut count = 0;
+		(0..cells.len())
+			.map(|i| {
+				let root = find(&mut parents, i);
+				if ids[root] == u32::MAX {
+					ids[root] = count;
+					count += 1;
+				}
+				ids[root]
+			})
+			.collect()
+	}
+
+	/// Get the coordinates of living cells with a living neighbor across an edge as packed x, y pairs.
+	///
+	/// Respects the configured neighborhood, with a dead boundary no cells interact across edges.
+	/// Note: only adjacent cells are considered, even with the `MooreRange2` neighborhood.
+	pub fn edge_interacting_cells(&self) -> Vec<u32> {
+		self.live_cells()
+			.chunks_exact(2)
+			.filter(|xy| {
+				let (x, y) = (xy[0], xy[1]);
+				neighbor_offsets(self.height).any(|yo| neighbor_offsets(self.width).any(|xo| {
+					if xo == 0 && yo == 0 || !self.is_neighbor_inside(x, xo, self.width) || !self.is_neighbor_inside(y, yo, self.height) {
+						return false;
+					}
+					if self.neighborhood == Neighborhood::VonNeumann && xo != 0 && yo != 0 {
+						return false;
+					}
+					(crosses_edge(x, xo, self.width) || crosses_edge(y, yo, self.height))
+						&& self.get_cell(wrapping_offset(x, xo, self.width), wrapping_offset(y, yo, self.height))
+				}))
+			})
+			.flatten()
+			.copied()
+			.collect()
+	}
+
+	/// Get the number of cells that differ between two universes.
+	///
+	/// Returns `None` if the universes have different dimensions.
+	pub fn difference(&self, other: &Universe) -> Option<u32> {
+		if self.width != other.width || self.height != other.height {
+			return None;
+		}
+		Some(self.row_major_cells().hamming_distance(&other.row_major_cells()) as u32)
+	}
+
+	/// Change the dimensions of the universe, keeping cells anchored at the top-left.
+	///
+	/// Cells that no longer fit are dropped, the generation count is preserved.
+	/// Panics if either dimension is 0, see `try_resize` for a checked version.
+	/// Note: the history of previous generations is discarded.
+	pub fn resize(&mut self, width: u32, height: u32) {
+		self.try_resize(width, height).expect("universe dimensions should be positive")
+	}
+
+	/// Change the dimensions of the universe like `resize`, or get an error if either dimension is 0.
+	///
+	/// The universe is unchanged on error.
+	pub fn try_resize(&mut self, width: u32, height: u32) -> Result<(), UniverseError> {
+		if width == 0 || height == 0 {
+			return Err(UniverseError::ZeroDimension { width, height });
+		}
+		let len = self.layout.len(width, height);
+		let mut cells = BitStore::empty(len);
+		for y in 0..height.min(self.height) {
+			for x in 0..width.min(self.width) {
+				cells.set(self.layout.idx(x, y, width), self.get_cell(x, y));
+			}
+		}
+
+		self.width = width;
+		self.height = height;
+		self.replace_cells(cells);
+		self.next = BitStore::empty(len);
+		self.changed = BitStore::empty(len);
+		self.active = BitStore::empty(len);
+		self.history.clear();
+		if !self.ages.is_empty() {
+			self.ages = vec![0; len];
+		}
+		if !self.dying.is_empty() {
+			self.dying = vec![0; len];
+		}
+		Ok(())
+	}
+
+	/// Move every cell by provided offset, wrapping around the edges.
+	pub fn shift(&mut self, dx: i32, dy: i32) {
+		let dx = (dx as i64).rem_euclid(self.width as i64) as u32;
+		let dy = (dy as i64).rem_euclid(self.height as i64) as u32;
+		if dx == 0 && self.layout == Layout::RowMajor && self.dying.is_empty() {
+			// whole rows move, so the rows can stay packed
+			let cells = self.cells.shift_right(dy as usize * self.width as usize);
+			self.replace_cells(cells);
+			return;
+		}
+		let (width, height) = (self.width, self.height);
+		self.remap(|x, y| (wrapping_offset(x, dx, width), wrapping_offset(y, dy, height)));
+	}
+
+	/// Mirror the universe left to right.
+	pub fn flip_horizontal(&mut self) {
+		let width = self.width;
+		self.remap(|x, y| (width - 1 - x, y));
+	}
+
+	/// Mirror the universe top to bottom.
+	pub fn flip_vertical(&mut self) {
+		let height = self.height;
+		self.remap(|x, y| (x, height - 1 - y));
+	}
+
+	/// Rotate a square universe clockwise by 90 degrees.
+	///
+	/// Returns false and leaves the universe unchanged if it is not square.
+	pub fn rotate_right(&mut self) -> bool {
+		self.rotate(Transformation::RotateRight)
+	}
+
+	/// Rotate a square universe counter-clockwise by 90 degrees.
+	///
+	/// Returns false and leaves the universe unchanged if it is not square.
+	pub fn rotate_left(&mut self) -> bool {
+		self.rotate(Transformation::RotateLeft)
+	}
+
+	/// Set every cell in provided region to given state.
+	///
+	/// Note: the region wraps around the edges of the universe.
+	pub fn fill_region(&mut self, x0: u32, y0: u32, w: u32, h: u32, alive: bool) {
+		self.fill_region_with(x0, y0, w, h, || alive);
+	}
+
+	/// Fill provided region with random cells.
+	///
+	/// Note: the region wraps around the edges of the universe.
+	pub fn randomize_region(&mut self, x0: u32, y0: u32, w: u32, h: u32) {
+		self.randomize_region_with(x0, y0, w, h, &mut Rng::from_entropy());
+	}
+
+	/// Flip the state of every cell in provided region.
+	///
+	/// Note: the region wraps around the edges of the universe,
+	/// its size is clamped to that of the universe so no cell is flipped twice.
+	pub fn invert_region(&mut self, x0: u32, y0: u32, w: u32, h: u32) {
+		for y in 0..h.min(self.height) {
+			for x in 0..w.min(self.width) {
+				self.toggle_cell(wrapping_offset(x0, x, self.width), wrapping_offset(y0, y, self.height));
+			}
+		}
+	}
+
+	/// Flip the state of every cell in the universe.
+	pub fn invert(&mut self) {
+		match self.layout {
+			Layout::RowMajor => self.cells.invert(),
+			// padding within tiles has to stay dead
+			_ => self.invert_region(0, 0, self.width, self.height),
+		}
+	}
+
+	/// Copy the cells of provided region into a standalone pattern.
+	///
+	/// Note: like other regions the region wraps around the edges of the universe,
+	/// its size is clamped to that of the universe.
+	pub fn copy_region(&self, x0: u32, y0: u32, w: u32, h: u32) -> Pattern {
+		Pattern::from_fn(w.min(self.width), h.min(self.height), |x, y| {
+			self.get_cell(wrapping_offset(x0, x, self.width), wrapping_offset(y0, y, self.height))
+		})
+	}
+
+	/// Paste provided pattern with its top-left corner at provided coordinates.
+	///
+	/// When merging the living cells of the pattern are added to the universe,
+	/// otherwise the whole rectangle of the pattern is overwritten.
+	/// Note: the pattern wraps around the edges of the universe.
+	pub fn paste(&mut self, pattern: &Pattern, dx: u32, dy: u32, merge: bool) {
+		for y in 0..pattern.height() {
+			for x in 0..pattern.width() {
+				let alive = pattern.get(x, y);
+				if alive || !merge {
+					self.set_cell(wrapping_offset(dx, x, self.width), wrapping_offset(dy, y, self.height), alive);
+				}
+			}
+		}
+	}
+
+	/// Kill all cells and reset the generation count.
+	///
+	/// The history, the cells changed by the last tick, the population log and cell ages are reset too.
+	pub fn clear(&mut self) {
+		self.cells.clear();
+		self.changed.clear();
+		self.dying.iter_mut().for_each(|state| *state = 0);
+		self.ages.iter_mut().for_each(|age| *age = 0);
+		self.history.clear();
+		self.population_log.clear();
+		self.generation = 0;
+	}
+
+	/// Get the state of the cell at provided coordinates, 0 for dead, 1 for alive and higher for dying cells.
+	///
+	/// Note: coordinates outside the universe wrap around.
+	pub fn cell_state(&self, x: u32, y: u32) -> u8 {
+		let idx = self.idx(x, y);
+		if self.cells.get(idx) {
+			1
+		} else {
+			self.dying.get(idx).copied().unwrap_or(0)
+		}
+	}
+
+	/// Get the cell state at provided coordinates.
+	///
+	/// True means 'alive', false means 'dead'.
+	/// Note: coordinates outside the universe wrap around.
+	pub fn get_cell(&self, x: u32, y: u32) -> bool {
+		self.cells.get(self.idx(x, y))
+	}
+
+	/// Get the number of living neighbors of provided cell.
+	///
+	/// Respects the configured boundary and neighborhood, coordinates outside the universe wrap around.
+	pub fn neighbor_count(&self, x: u32, y: u32) -> u32 {
+		self.live_neighbor_count(x % self.width, y % self.height)
+	}
+
+	/// Sum the weights of living cells around provided cell with the kernel, see `set_kernel`.
+	///
+	/// Without a kernel this is the number of living neighbors.
+	/// Respects the configured boundary, coordinates outside the universe wrap around.
+	pub fn weighted_neighbor_sum(&self, x: u32, y: u32) -> i32 {
+		let (x, y) = (x % self.width, y % self.height);
+		let kernel = match &self.kernel {
+			Some(kernel) => kernel,
+			None => return self.live_neighbor_count(x, y) as i32,
+		};
+		let (width, height) = (self.width as i64, self.height as i64);
+		kernel.iter()
+			.filter_map(|(dx, dy, weight)| {
+				let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+				let inside = (0..width).contains(&nx) && (0..height).contains(&ny);
+				if !inside && self.boundary == Boundary::Dead {
+					return None;
+				}
+				let idx = self.idx(nx.rem_euclid(width) as u32, ny.rem_euclid(height) as u32);
+				Some(weight * self.cells.get(idx) as i32)
+			})
+			.sum()
+	}
+
+	/// Count neighbors by summing the weights of living cells around each cell instead of using the neighborhood.
+	///
+	/// The weights are the row-major `(2 * radius + 1)` cells wide square centered on the cell,
+	/// so Conway's neighborhood is radius 1 with all weights 1 except the center.
+	/// Sums that are negative or too large for the rule never satisfy it.
+	/// Note: on universes narrower than the kernel the same cell may be weighted several times.
+	pub fn set_kernel(&mut self, radius: u32, weights: Vec<i32>) -> Result<(), UniverseError> {
+		self.kernel = Some(Kernel::new(radius, weights)?);
+		Ok(())
+	}
+
+	/// Go back to counting neighbors with the neighborhood.
+	pub fn clear_kernel(&mut self) {
+		self.kernel = None;
+	}
+
+	/// Toggle provided cell.
+	///
+	/// Same as `toggle_cell`.
+	pub fn toggle(&mut self, x: u32, y: u32) {
+		self.toggle_cell(x, y);
+	}
+
+	/// Toggle provided cell.
+	///
+	/// Note: coordinates outside the universe wrap around.
+	pub fn toggle_cell(&mut self, x: u32, y: u32) {
+		let idx = self.idx(x, y);
+		self.cells.set(idx, !self.cells.get(idx));
+	}
+
+	/// Set provided cell to be alive or dead.
+	///
+	/// Note: coordinates outside the universe wrap around.
+	pub fn set_cell(&mut self, x: u32, y: u32, alive: bool) {
+		let idx = self.idx(x, y);
+		self.cells.set(idx, alive);
+	}
+
+	/// Set all cells provided as packed x, y pairs to be alive or dead.
+	///
+	/// Note: coordinates outside the universe wrap around, a trailing unpaired value is ignored.
+	pub fn set_cells_packed(&mut self, coords: &[u32], alive: bool) {
+		let indices: Vec<_> = coords.chunks_exact(2)
+			.map(|xy| self.idx(xy[0], xy[1]))
+			.collect();
+		self.cells.set_many(&indices, alive);
+	}
+
+	/// Spawn a randomly transformed glider at provided coordinates.
+	pub fn spawn_glider(&mut self, x: u32, y: u32) {
+		let tr = Transformation::random();
+		let x = wrapping_offset(x, self.width - 1, self.width);
+		let y = wrapping_offset(y, self.height - 1, self.height);
+		self.place_transformed(shape::GLIDER.iter().copied(), x, y, 3, 3, tr);
+	}
+}
+
+// Universes are equal if their cells are, regardless of generation, history or settings.
+impl PartialEq for Universe {
+	fn eq(&self, other: &Self) -> bool {
+		self.width == other.width
+			&& self.height == other.height
+			&& *self.row_major_cells() == *other.row_major_cells()
+	}
+}
+
+impl Eq for Universe {}
+
+// The cells are summarized, dumping them is unreadable for all but the smallest universes.
+impl std::fmt::Debug for Universe {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.debug_struct("Universe")
+			.field("width", &self.width)
+			.field("height", &self.height)
+			.field("generation", &self.generation)
+			.field("population", &self.population())
+			.finish()
+	}
+}
+
+impl std::fmt::Display for Universe {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		f.write_str(&self.render_string('X', '-'))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	
+	#[test]
+	fn test_neighbor_count() {
+		// leave an empty row and column to avoid wrapping artifacts
+		let mut universe = Universe::empty(4, 4);
+		universe.place([(1, 0), (1, 1), (1, 2)], 0, 0);
+
+		assert_eq!(universe.live_neighbor_count(0, 0), 2);
+		assert_eq!(universe.live_neighbor_count(1, 0), 1);
+		assert_eq!(universe.live_neighbor_count(2, 0), 2);
+		
+		assert_eq!(universe.live_neighbor_count(0, 1), 3);
+		assert_eq!(universe.live_neighbor_count(1, 1), 2);
+		assert_eq!(universe.live_neighbor_count(2, 1), 3);
+		
+		assert_eq!(universe.live_neighbor_count(0, 2), 2);
+		assert_eq!(universe.live_neighbor_count(1, 2), 1);
+		assert_eq!(universe.live_neighbor_count(2, 2), 2);
+
+		// check wrapping neighbors
+		assert_eq!(universe.live_neighbor_count(1, 3), 2);
+	}
+
+	#[test]
+	fn test_public_neighbor_count() {
+		let mut universe = Universe::empty(4, 4);
+		universe.place([(1, 0), (1, 1), (1, 2)], 0, 0);
+
+		let expected = [[2, 1, 2], [3, 2, 3], [2, 1, 2]];
+		for (y, row) in expected.iter().enumerate() {
+			for (x, &count) in row.iter().enumerate() {
+				assert_eq!(universe.neighbor_count(x as u32, y as u32), count);
+			}
+		}
+		assert_eq!(universe.neighbor_count(1, 3), 2);
+		assert_eq!(universe.neighbor_count(5, 7), 2);
+
+		universe.set_boundary(Boundary::Dead);
+		assert_eq!(universe.neighbor_count(1, 3), 1);
+	}
+
+	#[test]
+	fn test_narrow_neighbor_count() {
+		let mut column = Universe::empty(1, 5);
+		column.place([(0, 1), (0, 2), (0, 3)], 0, 0);
+		let mut row = Universe::empty(5, 1);
+		row.place([(1, 0), (2, 0), (3, 0)], 0, 0);
+
+		for (i, &expected) in [1, 1, 2, 1, 1].iter().enumerate() {
+			assert_eq!(column.live_neighbor_count(0, i as u32), expected);
+			assert_eq!(row.live_neighbor_count(i as u32, 0), expected);
+		}
+
+		column.tick();
+		let mut expected = Universe::empty(1, 5);
+		expected.place([(0, 2)], 0, 0);
+		assert_eq!(column, expected);
+
+		row.tick();
+		let mut expected = Universe::empty(5, 1);
+		expected.place([(2, 0)], 0, 0);
+		assert_eq!(row, expected);
+	}
+
+	#[test]
+	fn test_highlife_replicator() {
+		const REPLICATOR: &[(u32, u32)] = &[
+			(2, 0), (3, 0), (4, 0), (1, 1), (4, 1), (0, 2), (4, 2), (0, 3), (3, 3), (0, 4), (1, 4), (2, 4),
+		];
+		let mut conway = Universe::empty(16, 16);
+		conway.place(REPLICATOR.iter().copied(), 5, 5);
+		let mut highlife = Universe::empty(16, 16);
+		highlife.place(REPLICATOR.iter().copied(), 5, 5);
+		highlife.set_rule_from_str("B36/S23").unwrap();
+
+		// the second generation has a dead cell with exactly 6 living neighbors
+		conway.tick();
+		highlife.tick();
+		assert_eq!(conway.cells(), highlife.cells());
+		assert_eq!(highlife.live_neighbor_count(7, 7), 6);
+
+		conway.tick();
+		highlife.tick();
+		assert!(highlife.get_cell(7, 7));
+		conway.toggle_cell(7, 7);
+		assert_eq!(conway.cells(), highlife.cells());
+	}
+
+	#[test]
+	fn test_dead_boundary() {
+		let mut toroidal = Universe::empty(8, 8);
+		toroidal.place(shape::GLIDER.iter().copied(), 1, 1);
+		let mut dead = Universe::empty(8, 8);
+		dead.place(shape::GLIDER.iter().copied(), 1, 1);
+		dead.set_boundary(Boundary::Dead);
+
+		for _ in 0..8 {
+			toroidal.tick();
+			dead.tick();
+		}
+
+		// the glider moves by a cell diagonally every 4 generations
+		let mut expected = Universe::empty(8, 8);
+		expected.place(shape::GLIDER.iter().copied(), 7, 7);
+		assert_eq!(toroidal, expected);
+
+		// the glider crashes into the corner and becomes a block
+		let mut expected = Universe::empty(8, 8);
+		expected.place([(0, 0), (1, 0), (0, 1), (1, 1)], 0, 0);
+		assert_eq!(dead.cells(), expected.cells());
+	}
+
+	#[test]
+	fn test_double_buffer() {
+		let mut universe = Universe::empty(5, 5);
+		universe.place([(1, 2), (2, 2), (3, 2)], 0, 0);
+		let mut expected = Universe::empty(5, 5);
+		expected.place([(2, 1), (2, 2), (2, 3)], 0, 0);
+
+		// the back buffer holds a previous generation, which must not leak into the next one
+		universe.tick();
+		assert_eq!(universe, expected);
+		universe.tick();
+		universe.tick();
+		assert_eq!(universe, expected);
+	}
+
+	#[test]
+	fn test_tick_many() {
+		let mut many = Universe::random_seeded(16, 16, 5);
+		let mut single = Universe::random_seeded(16, 16, 5);
+
+		many.tick_many(0);
+		assert_eq!(many, single);
+
+		many.tick_many(3);
+		single.tick();
+		single.tick();
+		single.tick();
+		assert_eq!(many, single);
+	}
+
+	#[test]
+	fn test_generation() {
+		let mut universe = Universe::empty(4, 4);
+		assert_eq!(universe.generation(), 0);
+
+		for _ in 0..5 {
+			universe.tick();
+		}
+		assert_eq!(universe.generation(), 5);
+
+		universe.tick_many(3);
+		assert_eq!(universe.generation(), 8);
+	}
+
+	#[test]
+	fn test_clear() {
+		let mut universe = Universe::empty(6, 6);
+		universe.place(shape::GLIDER.iter().copied(), 1, 1);
+		universe.tick();
+
+		universe.clear();
+		assert_eq!(universe.population(), 0);
+		assert_eq!(universe.generation(), 0);
+		assert_eq!((universe.width(), universe.height()), (6, 6));
+	}
+
+	#[test]
+	fn test_step_back_after_clear() {
+		let mut universe = Universe::empty(6, 6);
+		universe.place(shape::GLIDER.iter().copied(), 1, 1);
+		universe.enable_history(4);
+		universe.enable_population_log(4);
+		universe.tick_many(2);
+
+		universe.clear();
+		assert!(!universe.step_back());
+		assert_eq!(universe.population(), 0);
+		assert!(universe.changed_cells().is_empty());
+		assert!(universe.population_history().is_empty());
+	}
+
+	#[test]
+	fn test_from_rle() {
+		let universe = Universe::from_rle("x = 3, y = 3\nobo$b2o$bo!").unwrap();
+
+		let mut expected = Universe::empty(3, 3);
+		expected.place([(0, 0), (2, 0), (1, 1), (2, 1), (1, 2)], 0, 0);
+		assert_eq!(universe, expected);
+
+		assert_eq!(Universe::from_rle("3q!"), Err(RleError::InvalidCount));
+	}
+
+	#[test]
+	fn test_insert_rle() {
+		let mut universe = Universe::empty(20, 20);
+		universe.set_cell(11, 10, true);
+		universe.insert_rle("bo$2bo$3o!", 10, 10).unwrap();
+		assert_eq!(universe.live_cells(), &[11, 10, 12, 11, 10, 12, 11, 12, 12, 12]);
+
+		assert_eq!(universe.insert_rle("bo$2bq!", 0, 0), Err(RleError::UnexpectedChar('q')));
+	}
+
+	#[test]
+	fn test_rle_round_trip() {
+		let mut universe = Universe::empty(3, 3);
+		universe.place(shape::GLIDER.iter().copied(), 0, 0);
+
+		let rle = universe.to_rle();
+		assert_eq!(rle, "x = 3, y = 3, rule = B3/S23\n2o$obo$o!");
+		assert_eq!(Universe::from_rle(&rle), Ok(universe));
+	}
+
+	#[test]
+	fn test_place_transformed() {
+		let mut universe = Universe::empty(6, 6);
+		universe.place_transformed(shape::GLIDER.iter().copied(), 2, 1, 3, 3, Transformation::Reflect);
+
+		let mut expected = Universe::empty(6, 6);
+		expected.place([(2, 0), (0, 1), (2, 1), (1, 2), (2, 2)], 2, 1);
+		assert_eq!(universe, expected);
+	}
+
+	#[test]
+	fn test_difference() {
+		let universe = Universe::random_seeded(10, 10, 99);
+		let mut flipped = Universe::random_seeded(10, 10, 99);
+		assert_eq!(universe.difference(&flipped), Some(0));
+
+		flipped.toggle_cell(3, 7);
+		assert_eq!(universe.difference(&flipped), Some(1));
+		assert_eq!(flipped.difference(&universe), Some(1));
+
+		assert_eq!(universe.difference(&Universe::empty(10, 11)), None);
+	}
+
+	#[test]
+	fn test_live_cells() {
+		let mut universe = Universe::empty(9, 9);
+		assert!(universe.live_cells().is_empty());
+
+		universe.place(shape::GLIDER.iter().copied(), 4, 5);
+		assert_eq!(universe.live_cells(), &[4, 5, 5, 5, 4, 6, 6, 6, 4, 7]);
+
+		// padding bits are not cells
+		universe.cells.set(81, true);
+		assert_eq!(universe.live_cells().len(), 10);
+	}
+
+	#[test]
+	fn test_changed_cells() {
+		let mut universe = Universe::empty(5, 5);
+		universe.place(shape::BLINKER.iter().copied(), 1, 2);
+		assert!(universe.changed_cells().is_empty());
+
+		universe.tick();
+		assert_eq!(universe.changed_cells(), &[2, 1, 1, 2, 3, 2, 2, 3]);
+
+		let mut block = Universe::empty(5, 5);
+		block.place(shape::BLOCK.iter().copied(), 1, 1);
+		block.tick();
+		assert!(block.changed_cells().is_empty());
+	}
+
+	#[test]
+	fn test_snapshot_round_trip() {
+		let mut universe = Universe::random_seeded(20, 20, 11);
+		universe.tick_many(3);
+
+		let bytes = universe.serialize();
+		assert_eq!(bytes.len(), 16 + 50);
+
+		let restored = Universe::deserialize(&bytes).unwrap();
+		assert_eq!(restored, universe);
+		assert_eq!(restored.generation(), 3);
+
+		assert_eq!(Universe::deserialize(&bytes[..bytes.len() - 1]).err(), Some(DecodeError::LengthMismatch {
+			expected: 50,
+			actual: 49,
+		}));
+	}
+
+	#[test]
+	fn test_resize() {
+		let mut universe = Universe::empty(4, 4);
+		universe.place(shape::BLOCK.iter().copied(), 1, 1);
+		universe.tick();
+
+		universe.resize(8, 8);
+		let mut expected = Universe::empty(8, 8);
+		expected.place(shape::BLOCK.iter().copied(), 1, 1);
+		assert_eq!(universe, expected);
+		assert_eq!(universe.generation(), 1);
+
+		universe.resize(2, 3);
+		assert_eq!(universe.live_cells(), &[1, 1, 1, 2]);
+	}
+
+	#[test]
+	fn test_von_neumann_neighborhood() {
+		let mut universe = Universe::empty(5, 5);
+		universe.place([(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)], 1, 1);
+
+		assert_eq!(universe.live_neighbor_count(2, 2), 4);
+		assert_eq!(universe.live_neighbor_count(1, 1), 3);
+		assert_eq!(universe.live_neighbor_count(2, 1), 3);
+
+		universe.set_neighborhood(Neighborhood::VonNeumann);
+		assert_eq!(universe.live_neighbor_count(2, 2), 4);
+		assert_eq!(universe.live_neighbor_count(1, 1), 2);
+		assert_eq!(universe.live_neighbor_count(2, 1), 1);
+	}
+
+	#[test]
+	fn test_flip() {
+		let mut universe = Universe::empty(5, 4);
+		let mut original = Universe::empty(5, 4);
+		for universe in [&mut universe, &mut original] {
+			universe.place(shape::GLIDER.iter().copied(), 0, 0);
+		}
+
+		universe.flip_horizontal();
+		assert_eq!(universe.live_cells(), &[3, 0, 4, 0, 2, 1, 4, 1, 4, 2]);
+		universe.flip_horizontal();
+		assert_eq!(universe, original);
+
+		universe.flip_vertical();
+		assert_eq!(universe.live_cells(), &[0, 1, 0, 2, 2, 2, 0, 3, 1, 3]);
+		universe.flip_vertical();
+		assert_eq!(universe, original);
+	}
+
+	#[test]
+	fn test_rotate() {
+		let mut universe = Universe::empty(4, 4);
+		let mut original = Universe::empty(4, 4);
+		for universe in [&mut universe, &mut original] {
+			universe.place([(0, 0), (0, 1), (0, 2), (1, 2)], 1, 0);
+		}
+
+		assert!(universe.rotate_right());
+		assert_eq!(universe.live_cells(), &[1, 1, 2, 1, 3, 1, 1, 2]);
+		for _ in 0..3 {
+			assert!(universe.rotate_right());
+		}
+		assert_eq!(universe, original);
+
+		assert!(universe.rotate_left());
+		assert!(universe.rotate_right());
+		assert_eq!(universe, original);
+
+		let mut rectangle = Universe::empty(4, 3);
+		rectangle.place([(0, 0), (0, 1), (0, 2), (1, 2)], 1, 0);
+		assert!(!rectangle.rotate_left());
+		assert_eq!(rectangle.live_cells(), &[1, 0, 1, 1, 1, 2, 2, 2]);
+	}
+
+	#[test]
+	fn test_step_back() {
+		let mut universe = Universe::empty(5, 5);
+		universe.place(shape::BLINKER.iter().copied(), 1, 2);
+		assert!(!universe.step_back());
+
+		universe.enable_history(2);
+		universe.tick_many(3);
+		let earlier = universe.live_cells();
+		universe.tick();
+		let previous = universe.live_cells();
+		universe.tick();
+
+		assert!(universe.step_back());
+		assert_eq!(universe.live_cells(), previous.as_slice());
+
+		assert!(universe.step_back());
+		assert_eq!(universe.live_cells(), earlier.as_slice());
+		assert_eq!(universe.generation(), 3);
+
+		// only the 2 most recent generations are kept
+		assert!(!universe.step_back());
+
+		universe.tick();
+		universe.resize(6, 6);
+		assert!(!universe.step_back());
+	}
+
+	#[test]
+	fn test_render_rgba() {
+		let mut universe = Universe::empty(3, 2);
+		universe.set_cell(2, 1, true);
+
+		let pixels = universe.render_rgba(0x11223344, 0xAABBCCDD);
+		assert_eq!(pixels.len(), 3 * 2 * 4);
+		assert_eq!(&pixels[20..24], &[0x11, 0x22, 0x33, 0x44]);
+		assert!(pixels[..20].chunks(4).all(|pixel| pixel == [0xAA, 0xBB, 0xCC, 0xDD]));
+	}
+
+	#[test]
+	fn test_render_string() {
+		let mut universe = Universe::empty(4, 3);
+		universe.place(shape::BLOCK.iter().copied(), 1, 1);
+
+		assert_eq!(universe.render_string('#', '.'), "....\n.##.\n.##.\n");
+		assert_eq!(universe.to_string(), "----\n-XX-\n-XX-\n");
+	}
+
+	#[test]
+	fn test_from_string() {
+		let universe = Universe::from_string(".#\n.#.\n.#", '#');
+		assert_eq!((universe.width(), universe.height()), (3, 3));
+		assert_eq!(universe.live_cells(), &[1, 0, 1, 1, 1, 2]);
+
+		let mut glider = Universe::empty(5, 4);
+		glider.place(shape::GLIDER.iter().copied(), 1, 0);
+		let rendered = glider.render_string('o', ' ');
+		assert_eq!(Universe::from_string(&rendered, 'o'), glider);
+	}
+
+	#[test]
+	fn test_place_wrapping() {
+		let mut universe = Universe::empty(3, 5);
+		universe.place([(5, 8)], 0, 0);
+		assert_eq!(universe.live_cells(), &[2, 3]);
+
+		// 2^32 is not a multiple of either dimension
+		universe.clear();
+		universe.place([(u32::MAX, u32::MAX)], 1, 2);
+		assert_eq!(universe.live_cells(), &[1, 2]);
+		assert!(universe.get_cell(1, 2));
+	}
+
+	#[test]
+	fn test_from_bytes() {
+		let universe = Universe::from_bytes(3, 3, vec![0b0001_0000, 0b1111_1111]).unwrap();
+		assert!(universe.get_cell(1, 1));
+		assert!(universe.get_cell(2, 2));
+		assert!(!universe.get_cell(0, 0));
+		assert_eq!(universe.population(), 2);
+
+		assert_eq!(Universe::from_bytes(3, 3, vec![0]).err(), Some(DecodeError::LengthMismatch {
+			expected: 2,
+			actual: 1,
+		}));
+		assert_eq!(Universe::from_bytes(0, 3, vec![]).err(), Some(DecodeError::InvalidDimensions));
+	}
+
+	#[test]
+	fn test_from_bytes_with_order() {
+		let universe = Universe::from_bytes_with_order(3, 3, vec![0b0000_1000, 0b1111_1111], BitOrder::MsbFirst).unwrap();
+		assert_eq!(universe.live_cells(), &[1, 1, 2, 2]);
+		assert_eq!(universe, Universe::from_bytes(3, 3, vec![0b0001_0000, 0b1]).unwrap());
+
+		assert_eq!(Universe::from_bytes_with_order(3, 3, vec![0], BitOrder::MsbFirst).err(), Some(DecodeError::LengthMismatch {
+			expected: 2,
+			actual: 1,
+		}));
+	}
+
+	#[test]
+	fn test_sparse_tick() {
+		let mut dense = Universe::empty(64, 48);
+		let mut sparse = Universe::empty(64, 48);
+		for universe in [&mut dense, &mut sparse] {
+			universe.place(shape::GLIDER.iter().copied(), 62, 10);
+			universe.place(shape::LWSS.iter().copied(), 20, 46);
+		}
+		sparse.set_sparse(true);
+
+		for _ in 0..40 {
+			dense.tick();
+			sparse.tick();
+			assert_eq!(sparse, dense);
+			assert_eq!(sparse.changed_cells(), dense.changed_cells());
+		}
+
+		let mut dense = Universe::random_seeded(30, 20, 8);
+		dense.set_boundary(Boundary::Dead);
+		let mut sparse = Universe::random_seeded(30, 20, 8);
+		sparse.set_boundary(Boundary::Dead);
+		sparse.set_sparse(true);
+		for _ in 0..20 {
+			dense.tick();
+			sparse.tick();
+			assert_eq!(sparse, dense);
+		}
+	}
+
+	#[test]
+	fn test_fill_region() {
+		let mut universe = Universe::empty(5, 5);
+		universe.fill_region(1, 2, 2, 2, true);
+		assert_eq!(universe.difference(&Universe::empty(5, 5)), Some(4));
+		assert_eq!(universe.live_cells(), &[1, 2, 2, 2, 1, 3, 2, 3]);
+
+		universe.fill_region(4, 4, 2, 2, true);
+		assert_eq!(universe.live_cells(), &[0, 0, 4, 0, 1, 2, 2, 2, 1, 3, 2, 3, 0, 4, 4, 4]);
+
+		universe.fill_region(0, 0, 5, 5, false);
+		assert_eq!(universe.population(), 0);
+	}
+
+	#[test]
+	fn test_randomize_region() {
+		let mut universe = Universe::empty(20, 20);
+		universe.randomize_region_with(5, 5, 10, 10, &mut Rng::new(3));
+		assert!(universe.population() > 0);
+
+		let outside = universe.live_cells()
+			.chunks(2)
+			.filter(|cell| !(5..15).contains(&cell[0]) || !(5..15).contains(&cell[1]))
+			.count();
+		assert_eq!(outside, 0);
+	}
+
+	#[test]
+	fn test_random_with_density() {
+		assert_eq!(Universe::random_with_density_seeded(30, 30, 0.0, 1).population(), 0);
+		assert_eq!(Universe::random_with_density_seeded(30, 30, 1.0, 1).population(), 900);
+		assert_eq!(Universe::random_with_density_seeded(30, 30, 7.0, 1).population(), 900);
+
+		let population = Universe::random_with_density_seeded(100, 100, 0.3, 1).population();
+		assert!((2700..3300).contains(&population), "population of {} is not close to 3000", population);
+	}
+
+	#[test]
+	fn test_glider_gun() {
+		let gun = Universe::glider_gun(64, 48);
+		assert_eq!(gun.population(), 36);
+
+		let mut universe = Universe::glider_gun(64, 48);
+		universe.tick_many(30);
+		assert_eq!(universe.population(), 41);
+
+		// the gun returns to its original state with a new glider next to it
+		let mut glider = Universe::empty(64, 48);
+		glider.place([(0, 0), (0, 2), (1, 1), (1, 2), (2, 1)], 24, 10);
+		assert_eq!(universe.cells().xor(gun.cells()), *glider.cells());
+	}
+
+	#[test]
+	fn test_equality_ignores_metadata() {
+		let mut universe = Universe::empty(6, 6);
+		universe.place(shape::BLOCK.iter().copied(), 2, 2);
+		universe.enable_history(4);
+		universe.tick_many(3);
+
+		let mut other = Universe::empty(6, 6);
+		other.place(shape::BLOCK.iter().copied(), 2, 2);
+		other.set_rule_from_str("B36/S23").unwrap();
+		other.set_boundary(Boundary::Dead);
+		other.set_neighborhood(Neighborhood::VonNeumann);
+
+		assert_ne!(universe.generation(), other.generation());
+		assert_eq!(universe, other);
+
+		other.toggle_cell(0, 0);
+		assert_ne!(universe, other);
+		assert_ne!(Universe::empty(6, 6), Universe::empty(4, 9));
+	}
+
+	#[test]
+	fn test_population_in() {
+		let mut universe = Universe::empty(8, 8);
+		universe.place(shape::BLOCK.iter().copied(), 3, 3);
+
+		assert_eq!(universe.population_in(0, 0, 4, 4), 1);
+		assert_eq!(universe.population_in(4, 0, 4, 8), 2);
+		assert_eq!(universe.population_in(3, 3, 2, 2), 4);
+		assert_eq!(universe.population_in(0, 0, 100, 100), 4);
+
+		// the region wraps around the edges
+		universe.clear();
+		universe.place(shape::BLOCK.iter().copied(), 7, 7);
+		assert_eq!(universe.population_in(6, 6, 3, 3), 4);
+		assert_eq!(universe.population_in(0, 0, 1, 1), 1);
+	}
+
+	#[test]
+	fn test_predecessor_exists() {
+		// every cell of a 3x3 torus neighbors all the others, so only empty,
+		// full and 4 cell states can be reached
+		let mut universe = Universe::empty(3, 3);
+		assert_eq!(universe.predecessor_exists(), Ok(true));
+
+		universe.set_cell(1, 1, true);
+		assert_eq!(universe.predecessor_exists(), Ok(false));
+
+		universe.place([(0, 0), (2, 0), (0, 2)], 0, 0);
+		assert_eq!(universe.predecessor_exists(), Ok(true));
+
+		assert_eq!(Universe::empty(5, 4).predecessor_exists(), Err(UniverseError::TooLarge {
+			cells: 20,
+			max_cells: 16,
+		}));
+	}
+
+	#[test]
+	fn test_predecessor_exists_settings() {
+		// the same states are reachable whatever the layout
+		let mut tiled = Universe::empty_with_layout(3, 3, Layout::Tiled);
+		tiled.set_cell(1, 1, true);
+		assert_eq!(tiled.predecessor_exists(), Ok(false));
+		tiled.place([(0, 0), (2, 0), (0, 2)], 0, 0);
+		assert_eq!(tiled.predecessor_exists(), Ok(true));
+
+		// a kernel weighting only the cell itself with B1/S1 keeps every state as it is
+		let mut universe = Universe::empty(3, 3);
+		universe.set_rule_from_str("B1/S1").unwrap();
+		universe.set_kernel(0, vec![1]).unwrap();
+		universe.set_cell(1, 1, true);
+		assert_eq!(universe.predecessor_exists(), Ok(true));
+		universe.clear_kernel();
+		assert_eq!(universe.predecessor_exists(), Ok(false));
+
+		let universe = Universe::with_generations_rule(3, 3, 3, "B2/S").unwrap();
+		assert_eq!(universe.predecessor_exists(), Err(UniverseError::TooManyStates { states: 3 }));
+	}
+
+	#[test]
+	fn test_center_of_mass() {
+		fn assert_close(actual: Option<(f64, f64)>, expected: (f64, f64)) {
+			let (x, y) = actual.unwrap();
+			assert!((x - expected.0).abs() < 1e-9 && (y - expected.1).abs() < 1e-9, "{:?} != {:?}", (x, y), expected);
+		}
+
+		let mut universe = Universe::empty(8, 8);
+		assert_eq!(universe.center_of_mass(), None);
+
+		universe.set_cell(2, 5, true);
+		assert_close(universe.center_of_mass(), (2.0, 5.0));
+
+		universe.clear();
+		universe.place(shape::BLOCK.iter().copied(), 3, 3);
+		assert_close(universe.center_of_mass(), (3.5, 3.5));
+
+		// the block straddles both seams
+		universe.clear();
+		universe.place(shape::BLOCK.iter().copied(), 7, 7);
+		assert_close(universe.center_of_mass(), (7.5, 7.5));
+	}
+
+	#[test]
+	fn test_cells_snapshot() {
+		let universe = Universe::random_seeded(13, 7, 5);
+		let snapshot = universe.cells_snapshot();
+		assert_eq!(snapshot.len(), universe.cells_size());
+		assert_eq!(snapshot, universe.cells().as_bytes());
+	}
+
+	#[test]
+	fn test_detect_period() {
+		let mut universe = Universe::empty(8, 8);
+		universe.place(shape::BLOCK.iter().copied(), 2, 2);
+		assert_eq!(universe.detect_period(10), Some(1));
+
+		universe.clear();
+		universe.place(shape::BLINKER.iter().copied(), 2, 2);
+		let original = universe.cells().clone();
+		assert_eq!(universe.detect_period(10), Some(2));
+		assert_eq!(universe.detect_period(1), None);
+		assert_eq!(universe.cells(), &original);
+		assert_eq!(universe.generation(), 0);
+
+		// a glider never returns to its original position on a large enough board
+		universe.clear();
+		universe.place(shape::GLIDER.iter().copied(), 2, 2);
+		assert_eq!(universe.detect_period(8), None);
+	}
+
+	#[test]
+	fn test_set_cells_packed() {
+		let mut universe = Universe::empty(6, 6);
+		universe.set_cells_packed(&[0, 0, 2, 3, 5, 5, 7, 1, 4], true);
+		assert_eq!(universe.live_cells(), &[0, 0, 1, 1, 2, 3, 5, 5]);
+
+		universe.set_cells_packed(&[2, 3, 5, 5], false);
+		assert_eq!(universe.live_cells(), &[0, 0, 1, 1]);
+	}
+
+	#[test]
+	fn test_cells_version() {
+		let mut universe = Universe::empty(8, 8);
+		let version = universe.cells_version();
+
+		universe.tick();
+		universe.tick();
+		assert_eq!(universe.cells_version(), version);
+
+		universe.resize(10, 10);
+		assert_ne!(universe.cells_version(), version);
+	}
+
+	#[test]
+	fn test_is_stable() {
+		let mut universe = Universe::empty(8, 8);
+		universe.place(shape::BLOCK.iter().copied(), 2, 2);
+		assert!(universe.is_stable());
+		assert!(universe.is_stable_within(1));
+
+		universe.clear();
+		universe.place(shape::BLINKER.iter().copied(), 2, 2);
+		let original = universe.cells().clone();
+		assert!(!universe.is_stable());
+		assert!(!universe.is_stable_within(1));
+		assert!(universe.is_stable_within(2));
+		assert_eq!(universe.cells(), &original);
+		assert_eq!(universe.generation(), 0);
+	}
+
+	#[test]
+	fn test_is_stable_injection() {
+		// an injected cell stays alive despite dying of loneliness
+		let mut universe = Universe::empty(8, 8);
+		universe.set_cell(2, 2, true);
+		universe.set_injection(vec![(2, 2)]);
+		assert!(universe.is_stable());
+		universe.tick();
+		assert_eq!(universe.live_cells(), &[2, 2]);
+
+		// an injected cell appearing away from a still life changes the universe
+		let mut universe = Universe::empty(8, 8);
+		universe.place(shape::BLOCK.iter().copied(), 1, 1);
+		universe.set_injection(vec![(6, 6)]);
+		assert!(!universe.is_stable());
+		universe.tick();
+		assert_eq!(universe.population(), 5);
+	}
+
+	#[test]
+	fn test_checkerboard() {
+		assert_eq!(Universe::checkerboard(4, 4).population(), 8);
+		assert_eq!(Universe::checkerboard(5, 3).population(), 8);
+		assert_eq!(Universe::checkerboard(3, 3).render_string('X', '-'), "X-X\n-X-\nX-X\n");
+	}
+
+	#[test]
+	fn test_stripes() {
+		assert_eq!(Universe::stripes(7, 2, 2).render_string('X', '-'), "XX--XX-\nXX--XX-\n");
+		assert_eq!(Universe::stripes(4, 1, 0), Universe::stripes(4, 1, 1));
+		assert_eq!(Universe::stripes(10, 10, 3).population(), 60);
+	}
+
+	#[test]
+	fn test_ages() {
+		let mut universe = Universe::empty(8, 8);
+		universe.place(shape::BLOCK.iter().copied(), 1, 1);
+		universe.place(shape::BLINKER.iter().copied(), 4, 5);
+		assert_eq!(universe.ages_size(), 0);
+
+		universe.enable_ages(true);
+		assert_eq!(universe.ages_size(), 64);
+		universe.tick_many(3);
+
+		// block cells keep aging while blinker cells keep being reborn
+		assert!(shape::BLOCK.iter().all(|&(x, y)| universe.ages[universe.idx(x + 1, y + 1)] == 3));
+		assert_eq!(universe.ages[universe.idx(5, 5)], 3);
+		assert_eq!(universe.ages[universe.idx(5, 4)], 0);
+		assert_eq!(universe.ages.iter().filter(|&&age| age > 0).count(), 5);
+
+		universe.enable_ages(false);
+		assert_eq!(universe.ages_size(), 0);
+	}
+
+	#[test]
+	fn test_life106_round_trip() {
+		let glider = "#Life 1.06\n-1 -1\n0 -1\n-1 0\n1 0\n-1 1\n";
+		let universe = Universe::from_life106(glider, 8, 8);
+
+		let mut expected = Universe::empty(8, 8);
+		expected.place(shape::GLIDER.iter().copied(), 0, 0);
+		assert_eq!(universe, expected);
+
+		let encoded = universe.to_life106();
+		assert_eq!(Universe::from_life106(&encoded, 8, 8), universe);
+		assert_eq!(parse_life106(&encoded).len(), 5);
+	}
+
+	#[test]
+	fn test_stamp() {
+		let mut universe = Universe::empty(6, 4);
+		assert_eq!(universe.stamp(shape::BLINKER, 4, 1, false), 1);
+		assert_eq!(universe.live_cells(), &[4, 1, 5, 1]);
+
+		universe.clear();
+		assert_eq!(universe.stamp(shape::BLINKER, 4, 1, true), 0);
+		assert_eq!(universe.live_cells(), &[0, 1, 4, 1, 5, 1]);
+
+		universe.clear();
+		assert_eq!(universe.stamp(shape::BLOCK, u32::MAX, 0, false), 4);
+		assert_eq!(universe.population(), 0);
+		assert_eq!(universe.stamp(shape::BLOCK, 0, 0, false), 0);
+	}
+
+	#[test]
+	fn test_stamp_clipped_count() {
+		// a 3x3 square one cell past the right edge
+		let square: Vec<_> = (0..3).flat_map(|y| (0..3).map(move |x| (x, y))).collect();
+		let mut universe = Universe::empty(8, 8);
+
+		assert_eq!(universe.stamp(&square, 6, 2, false), 3);
+		assert_eq!(universe.population(), 6);
+		assert!(!universe.get_cell(0, 2));
+	}
+
+	#[test]
+	fn test_tiled_layout() {
+		let mut row_major = Universe::empty(13, 10);
+		let mut tiled = Universe::empty_with_layout(13, 10, Layout::Tiled);
+		for universe in [&mut row_major, &mut tiled] {
+			universe.place(shape::GLIDER.iter().copied(), 6, 5);
+		}
+
+		// the glider crosses tile boundaries as well as the edges of the universe
+		for _ in 0..40 {
+			row_major.tick();
+			tiled.tick();
+			assert_eq!(tiled, row_major);
+			assert_eq!(tiled.live_cells(), row_major.live_cells());
+			assert_eq!(tiled.population(), 5);
+		}
+		assert_eq!(tiled.render_string('X', '-'), row_major.render_string('X', '-'));
+		assert_eq!(tiled.render_rgba(1, 0), row_major.render_rgba(1, 0));
+		assert_eq!(tiled.serialize(), row_major.serialize());
+		assert_eq!(tiled.cells_snapshot(), row_major.cells_snapshot());
+	}
+
+	#[test]
+	fn test_tiled_layout_settings() {
+		let mut row_major = Universe::random_with_density_seeded(20, 17, 0.4, 3);
+		let mut tiled = Universe::empty_with_layout(20, 17, Layout::Tiled);
+		tiled.set_cells_packed(&row_major.live_cells(), true);
+		for universe in [&mut row_major, &mut tiled] {
+			universe.set_boundary(Boundary::Dead);
+			universe.set_sparse(true);
+			universe.tick_many(10);
+			universe.flip_horizontal();
+			universe.resize(25, 9);
+			universe.tick_many(10);
+		}
+		assert_eq!(tiled, row_major);
+		assert_eq!(tiled.difference(&row_major), Some(0));
+	}
+
+	#[test]
+	fn test_tick_naive() {
+		let settings = [
+			(70, 33, "B3/S23", Boundary::Toroidal, Neighborhood::Moore),
+			(64, 3, "B36/S23", Boundary::Dead, Neighborhood::Moore),
+			(17, 129, "B2/S013", Boundary::Toroidal, Neighborhood::VonNeumann),
+			(5, 5, "B1/S012345678", Boundary::Dead, Neighborhood::VonNeumann),
+		];
+		for (seed, &(width, height, rule, boundary, neighborhood)) in settings.iter().enumerate() {
+			let mut parallel = Universe::random_seeded(width, height, seed as u64);
+			let mut naive = Universe::random_seeded(width, height, seed as u64);
+			for universe in [&mut parallel, &mut naive] {
+				universe.set_rule_from_str(rule).unwrap();
+				universe.set_boundary(boundary);
+				universe.set_neighborhood(neighborhood);
+			}
+
+			for generation in 0..20 {
+				parallel.tick();
+				naive.tick_naive();
+				assert_eq!(parallel, naive, "{}x{} {} differs at generation {}", width, height, rule, generation);
+				assert_eq!(parallel.changed_cells(), naive.changed_cells());
+			}
+		}
+	}
+
+	#[test]
+	fn test_neighbor_count_table() {
+		let settings = [
+			(13, 9, Boundary::Toroidal, Neighborhood::Moore),
+			(13, 9, Boundary::Dead, Neighborhood::Moore),
+			(7, 5, Boundary::Toroidal, Neighborhood::VonNeumann),
+			(7, 5, Boundary::Dead, Neighborhood::VonNeumann),
+			(9, 6, Boundary::Toroidal, Neighborhood::MooreRange2),
+			(9, 6, Boundary::Dead, Neighborhood::MooreRange2),
+		];
+		for (seed, &(width, height, boundary, neighborhood)) in settings.iter().enumerate() {
+			let mut universe = Universe::random_seeded(width, height, seed as u64);
+			universe.set_boundary(boundary);
+			universe.set_neighborhood(neighborhood);
+			let radius = if neighborhood == Neighborhood::MooreRange2 { 2 } else { 1 };
+
+			for y in 0..height {
+				for x in 0..width {
+					// count every neighbor individually to compare with the table
+					let mut expected = 0;
+					for dy in -(radius as i64)..=radius as i64 {
+						for dx in -(radius as i64)..=radius as i64 {
+							let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+							let inside = (0..width as i64).contains(&nx) && (0..height as i64).contains(&ny);
+							let orthogonal = dx == 0 || dy == 0;
+							if dx == 0 && dy == 0 || !inside && boundary == Boundary::Dead
+								|| neighborhood == Neighborhood::VonNeumann && !orthogonal {
+								continue;
+							}
+							expected += universe.get_cell(nx.rem_euclid(width as i64) as u32, ny.rem_euclid(height as i64) as u32) as u32;
+						}
+					}
+					assert_eq!(universe.live_neighbor_count(x, y), expected, "{:?} {:?} at {}, {}", boundary, neighborhood, x, y);
+				}
+			}
+		}
+		assert_eq!(POPCOUNT_TABLE[0b1011_0110], 5);
+		assert_eq!(POPCOUNT_TABLE[0xFF], 8);
+	}
+
+	#[test]
+	fn test_connected_components() {
+		let mut universe = Universe::empty(10, 10);
+		assert!(universe.connected_components().is_empty());
+
+		universe.place(shape::BLOCK.iter().copied(), 1, 1);
+		universe.place(shape::BLOCK.iter().copied(), 6, 2);
+		universe.set_cell(3, 3, true);
+		assert_eq!(universe.connected_components(), &[0, 0, 0, 0, 1, 1, 0, 1, 1]);
+
+		// blocks on opposite edges touch through the seam
+		universe.clear();
+		universe.place(shape::BLOCK.iter().copied(), 0, 4);
+		universe.place(shape::BLOCK.iter().copied(), 8, 4);
+		assert_eq!(universe.connected_components(), &[0; 8]);
+
+		universe.set_boundary(Boundary::Dead);
+		assert_eq!(universe.connected_components(), &[0, 0, 1, 1, 0, 0, 1, 1]);
+	}
+
+	#[test]
+	fn test_invert_region() {
+		let mut universe = Universe::empty(5, 5);
+		universe.set_cell(1, 1, true);
+		universe.invert_region(0, 0, 2, 2);
+		assert_eq!(universe.live_cells(), &[0, 0, 1, 0, 0, 1]);
+
+		universe.invert_region(4, 4, 100, 2);
+		assert_eq!(universe.population(), 9);
+	}
+
+	#[test]
+	fn test_invert() {
+		let mut universe = Universe::empty(5, 3);
+		universe.place(shape::BLINKER.iter().copied(), 1, 1);
+		universe.invert();
+		assert_eq!(universe.population(), 12);
+		assert_eq!(universe.render_string('X', '-'), "XXXXX\nX---X\nXXXXX\n");
+
+		let mut tiled = Universe::empty_with_layout(5, 3, Layout::Tiled);
+		tiled.place(shape::BLINKER.iter().copied(), 1, 1);
+		tiled.invert();
+		assert_eq!(tiled, universe);
+		assert_eq!(tiled.population(), 12);
+	}
+
+	#[test]
+	fn test_try_empty() {
+		assert_eq!(Universe::try_empty(0, 5), Err(UniverseError::ZeroDimension { width: 0, height: 5 }));
+		assert!(Universe::try_empty(3, 0).is_err());
+		assert_eq!(Universe::try_empty(3, 5), Ok(Universe::empty(3, 5)));
+	}
+
+	#[test]
+	#[should_panic]
+	fn test_empty_zero_dimension() {
+		Universe::empty(0, 0);
+	}
+
+	#[test]
+	fn test_brians_brain() {
+		let mut universe = Universe::with_generations_rule(6, 6, 3, "B2/S/").unwrap();
+		universe.place([(2, 2), (3, 2)], 0, 0);
+
+		universe.tick();
+		assert_eq!(universe.live_cells(), &[2, 1, 3, 1, 2, 3, 3, 3]);
+		assert_eq!(universe.cell_state(2, 2), 2);
+		assert_eq!(universe.cell_state(3, 2), 2);
+		assert_eq!(universe.cell_state(0, 0), 0);
+		assert!(!universe.is_stable());
+
+		// dying cells die and the newborn cells start dying
+		universe.tick();
+		assert_eq!(universe.cell_state(2, 2), 0);
+		assert_eq!(universe.cell_state(2, 1), 2);
+		assert!(!universe.get_cell(2, 2));
+
+		assert_eq!(Universe::with_generations_rule(6, 6, 1, "B2/S").unwrap_err(), RuleError::TooFewStates(1));
+		assert_eq!(Universe::with_generations_rule(6, 6, 3, "B2").unwrap_err(), RuleError::Malformed);
+	}
+
+	#[test]
+	fn test_delta() {
+		let mut universe = Universe::empty(12, 12);
+		universe.place(shape::GLIDER.iter().copied(), 10, 10);
+		let previous = Universe::from_bytes(12, 12, universe.cells_snapshot()).unwrap();
+
+		universe.tick();
+		let delta = universe.delta_since(previous.cells());
+		assert!(delta.len() < universe.cells_size());
+
+		let mut reconstructed = previous;
+		reconstructed.apply_delta(&delta).unwrap();
+		assert_eq!(reconstructed, universe);
+
+		assert_eq!(reconstructed.apply_delta(&[0x80]), Err(DeltaError::Truncated));
+		assert_eq!(reconstructed.apply_delta(&[1, 0x7F, 0x7F]), Err(DeltaError::IndexOutOfRange(255)));
+		assert_eq!(reconstructed, universe);
+	}
+
+	#[test]
+	fn test_kernel() {
+		let conway = vec![1, 1, 1, 1, 0, 1, 1, 1, 1];
+		for boundary in [Boundary::Toroidal, Boundary::Dead] {
+			let mut weighted = Universe::random_seeded(11, 9, 5);
+			let mut standard = Universe::random_seeded(11, 9, 5);
+			weighted.set_boundary(boundary);
+			standard.set_boundary(boundary);
+			weighted.set_kernel(1, conway.clone()).unwrap();
+
+			for y in 0..9 {
+				for x in 0..11 {
+					assert_eq!(weighted.weighted_neighbor_sum(x, y), standard.neighbor_count(x, y) as i32);
+				}
+			}
+			weighted.tick_many(10);
+			standard.tick_many(10);
+			assert_eq!(weighted, standard);
+		}
+	}
+
+	#[test]
+	fn test_weighted_kernel() {
+		let mut universe = Universe::empty(7, 7);
+		universe.place([(3, 1), (3, 3), (0, 3)], 0, 0);
+		// cells two above count double, cells to the left subtract
+		let mut weights = vec![0; 25];
+		weights[2] = 2;
+		weights[10] = -1;
+		weights[11] = -1;
+		universe.set_kernel(2, weights).unwrap();
+
+		assert_eq!(universe.weighted_neighbor_sum(3, 3), 2);
+		assert_eq!(universe.weighted_neighbor_sum(1, 3), -1);
+		assert_eq!(universe.weighted_neighbor_sum(4, 3), -1);
+		assert_eq!(universe.weighted_neighbor_sum(3, 5), 2);
+
+		assert!(universe.set_kernel(1, vec![1; 4]).is_err());
+		universe.clear_kernel();
+		assert_eq!(universe.weighted_neighbor_sum(3, 3), 0);
+	}
+
+	#[test]
+	fn test_copy_paste() {
+		let mut source = Universe::empty(6, 6);
+		source.place(shape::BLOCK.iter().copied(), 1, 1);
+		let pattern = source.copy_region(0, 0, 4, 4);
+		assert_eq!((pattern.width(), pattern.height(), pattern.population()), (4, 4, 4));
+
+		let mut universe = Universe::empty(12, 12);
+		universe.paste(&pattern, 5, 6, true);
+		let mut expected = Universe::empty(12, 12);
+		expected.place(shape::BLOCK.iter().copied(), 6, 7);
+		assert_eq!(universe, expected);
+
+		// overwriting clears the rest of the rectangle, merging keeps it
+		universe.fill_region(0, 0, 12, 12, true);
+		universe.paste(&pattern, 5, 6, true);
+		assert_eq!(universe.population(), 144);
+		universe.paste(&pattern, 5, 6, false);
+		assert_eq!(universe.population(), 144 - 12);
+
+		// regions wrap around the edges
+		assert_eq!(source.copy_region(0, 0, 100, 1).width(), 6);
+		assert_eq!(source.copy_region(5, 5, 3, 3).population(), 1);
+	}
+
+	#[test]
+	fn test_shift() {
+		let mut universe = Universe::empty(5, 4);
+		universe.set_cell(4, 3, true);
+
+		universe.shift(1, 0);
+		assert_eq!(universe.live_cells(), &[0, 3]);
+		universe.shift(0, 1);
+		assert_eq!(universe.live_cells(), &[0, 0]);
+		universe.shift(-2, -5);
+		assert_eq!(universe.live_cells(), &[3, 3]);
+
+		let mut glider = Universe::empty(8, 8);
+		glider.place(shape::GLIDER.iter().copied(), 0, 0);
+		let mut expected = Universe::empty(8, 8);
+		expected.place(shape::GLIDER.iter().copied(), 6, 7);
+		glider.shift(-2, 7);
+		assert_eq!(glider, expected);
+	}
+
+	#[test]
+	fn test_still_lifes() {
+		let mut universe = Universe::empty(12, 12);
+		universe.place(shape::BLOCK.iter().copied(), 1, 1);
+		universe.place(shape::BLINKER.iter().copied(), 6, 6);
+		let original = universe.cells().clone();
+
+		let still_lifes = universe.still_lifes();
+		assert_eq!(still_lifes.len(), 1);
+		assert_eq!((still_lifes[0].width(), still_lifes[0].height(), still_lifes[0].population()), (2, 2, 4));
+		assert_eq!(universe.cells(), &original);
+		assert_eq!(universe.generation(), 0);
+
+		// a beehive straddling the corner is extracted whole
+		universe.clear();
+		universe.place(shape::BEEHIVE.iter().copied(), 10, 11);
+		let still_lifes = universe.still_lifes();
+		assert_eq!(still_lifes.len(), 1);
+		assert_eq!(still_lifes[0], Pattern::from_fn(4, 3, |x, y| shape::BEEHIVE.contains(&(x, y))));
+	}
+
+	#[test]
+	fn test_tick_checked() {
+		// the L-tromino becomes a block after one generation
+		let mut universe = Universe::empty(8, 8);
+		universe.place([(2, 2), (3, 2), (2, 3)], 0, 0);
+		assert!(universe.tick_checked());
+		assert_eq!(universe.population(), 4);
+		assert!(!universe.tick_checked());
+		assert!(!universe.tick_checked());
+
+		universe.set_sparse(true);
+		assert!(!universe.tick_checked());
+		universe.place(shape::BLINKER.iter().copied(), 5, 5);
+		assert!(universe.tick_checked());
+	}
+
+	#[test]
+	fn test_population_log() {
+		let mut universe = Universe::empty(8, 8);
+		universe.place(shape::BLINKER.iter().copied(), 2, 2);
+		universe.place([(6, 6)], 0, 0);
+		universe.tick();
+		assert!(universe.population_history().is_empty());
+
+		universe.enable_population_log(3);
+		universe.tick_many(2);
+		assert_eq!(universe.population_history(), &[3, 3]);
+
+		// the lone cell died in the first tick, the oldest samples are dropped
+		universe.place([(0, 5), (1, 5), (0, 6), (1, 6)], 0, 0);
+		universe.tick_many(2);
+		assert_eq!(universe.population_history(), &[3, 7, 7]);
+
+		universe.detect_period(4);
+		assert_eq!(universe.population_history(), &[3, 7, 7]);
+		universe.enable_population_log(1);
+		assert_eq!(universe.population_history(), &[7]);
+	}
+
+	#[test]
+	fn test_tick_timing() {
+		thread_local!(static CALLS: std::cell::Cell<u32> = const { std::cell::Cell::new(0) });
+		/// Time that passes quadratically with the number of calls, so each tick takes longer.
+		fn clock() -> f64 {
+			let calls = CALLS.with(|calls| {
+				calls.set(calls.get() + 1);
+				calls.get()
+			});
+			(calls * calls) as f64
+		}
+
+		let mut universe = Universe::empty(8, 8);
+		universe.set_clock(clock);
+		universe.tick();
+		assert_eq!(universe.average_tick_ms(), 0.0);
+
+		// ticks take 3, 7 and 11 milliseconds
+		universe.enable_timing(2);
+		universe.tick_many(3);
+		assert_eq!(universe.tick_times.len(), 2);
+		assert_eq!(universe.average_tick_ms(), 9.0);
+
+		universe.enable_timing(1);
+		assert_eq!(universe.average_tick_ms(), 11.0);
+
+		// the default clock works outside the browser too
+		let mut universe = Universe::empty(8, 8);
+		universe.enable_timing(2);
+		universe.tick_many(2);
+		assert_eq!(universe.tick_times.len(), 2);
+		assert!(universe.average_tick_ms() >= 0.0);
+	}
+
+	#[test]
+	fn test_from_coords() {
+		let universe = Universe::from_coords(6, 6, &[1, 0, 2, 1, 0, 2, 1, 2, 8, 2]);
+		assert_eq!(universe.population(), 5);
+		assert_eq!(universe.live_cells(), &[1, 0, 2, 1, 0, 2, 1, 2, 2, 2]);
+	}
+
+	#[test]
+	fn test_edge_interacting_cells() {
+		let mut universe = Universe::empty(8, 8);
+		universe.place(shape::BLOCK.iter().copied(), 3, 3);
+		assert!(universe.edge_interacting_cells().is_empty());
+
+		universe.clear();
+		universe.place(shape::GLIDER.iter().copied(), 6, 2);
+		assert_eq!(universe.edge_interacting_cells(), &[7, 2, 0, 3]);
+
+		universe.clear();
+		universe.place(shape::BLOCK.iter().copied(), 7, 7);
+		assert_eq!(universe.edge_interacting_cells(), &[0, 0, 7, 0, 0, 7, 7, 7]);
+
+		universe.set_boundary(Boundary::Dead);
+		assert!(universe.edge_interacting_cells().is_empty());
+	}
+
+	#[test]
+	fn test_run() {
+		// a diagonal of three cells dies out in two generations
+		let mut universe = Universe::empty(8, 8);
+		universe.place([(1, 1), (2, 2), (3, 3)], 0, 0);
+		let mut populations = Vec::new();
+		let steps = universe.run(10, |universe| {
+			populations.push(universe.population());
+			universe.population() > 0
+		});
+		assert_eq!(steps, 2);
+		assert_eq!(populations, &[1, 0]);
+		assert_eq!(universe.generation(), 2);
+
+		assert_eq!(universe.run(5, |_| true), 5);
+		assert_eq!(universe.generation(), 7);
+	}
+
+	#[test]
+	fn test_soup() {
+		let soup = Universe::soup(16, 16, "k_abc123");
+		assert_eq!(soup, Universe::soup(16, 16, "k_abc123"));
+		assert_ne!(soup, Universe::soup(16, 16, "k_abc124"));
+		assert!(soup.population() > 0);
+	}
+
+	#[test]
+	fn test_tick_into() {
+		let mut universe = Universe::random_seeded(16, 16, 7);
+		let mut expected = Universe::random_seeded(16, 16, 7);
+		let mut scratch = BitStore::empty(universe.cells().valid_bits());
+
+		for _ in 0..8 {
+			let previous = universe.cells().clone();
+			universe.tick_into(&mut scratch);
+			expected.tick();
+
+			assert_eq!(universe, expected);
+			assert_eq!(scratch.as_bytes(), previous.as_bytes());
+		}
+	}
+
+	#[test]
+	fn test_moore_range2_neighborhood() {
+		let mut universe = Universe::empty(8, 8);
+		universe.set_neighborhood(Neighborhood::MooreRange2);
+		universe.set_cell(3, 3, true);
+
+		assert_eq!(universe.neighbor_count(3, 3), 0);
+		assert_eq!(universe.neighbor_count(4, 3), 1);
+		assert_eq!(universe.neighbor_count(5, 5), 1);
+		assert_eq!(universe.neighbor_count(1, 4), 1);
+		assert_eq!(universe.neighbor_count(6, 3), 0);
+		assert_eq!(universe.neighbor_count(3, 0), 0);
+		// offsets wrap around the edges
+		universe.set_cell(0, 0, true);
+		assert_eq!(universe.neighbor_count(6, 7), 1);
+		assert_eq!(universe.neighbor_count(2, 2), 2);
+
+		universe.set_boundary(Boundary::Dead);
+		assert_eq!(universe.neighbor_count(6, 7), 0);
+		assert_eq!(universe.neighbor_count(2, 2), 2);
+	}
+
+	#[test]
+	fn test_moore_range2_tick() {
+		let mut universe = Universe::empty(8, 8);
+		universe.set_neighborhood(Neighborhood::MooreRange2);
+		universe.set_rule_from_str("B2/S").unwrap();
+		universe.set_cell(2, 2, true);
+		universe.set_cell(4, 2, true);
+		universe.tick();
+
+		// only cells within range 2 of both live cells are born
+		assert_eq!(universe.population(), 15 - 2);
+		assert!(universe.get_cell(3, 4));
+		assert!(!universe.get_cell(3, 5));
+	}
+
+	#[test]
+	fn test_moore_range2_rule() {
+		let mut universe = Universe::empty(12, 12);
+		assert_eq!(universe.set_rule_from_str("R2,C0,M0,S20..24,B,NM"), Err(RuleError::Malformed));
+		universe.set_neighborhood(Neighborhood::MooreRange2);
+		universe.set_rule_from_str("R2,C0,M0,S20..24,B,NM").unwrap();
+		assert_eq!(universe.rule_string(), "R2,C0,M0,S20..24,NM");
+		universe.fill_region(3, 3, 5, 5, true);
+		universe.tick();
+
+		// only the center of the square has more than 20 living neighbors
+		assert_eq!(universe.live_cells(), &[5, 5]);
+	}
+
+	#[test]
+	fn test_state_hash() {
+		let mut universe = Universe::random_seeded(13, 7, 3);
+		let other = Universe::random_seeded(13, 7, 3);
+		assert_eq!(universe.state_hash(), other.state_hash());
+		assert_ne!(Universe::empty(4, 2).state_hash(), Universe::empty(2, 4).state_hash());
+
+		universe.toggle(12, 6);
+		assert_ne!(universe.state_hash(), other.state_hash());
+		universe.toggle(12, 6);
+		assert_eq!(universe.state_hash(), other.state_hash());
+	}
+
+	#[test]
+	fn test_shrink_to_fit() {
+		let mut universe = Universe::empty(10, 10);
+		universe.place(shape::BLOCK.iter().copied(), 4, 5);
+
+		assert_eq!(universe.shrink_to_fit(), (4, 5));
+		assert_eq!((universe.width(), universe.height()), (2, 2));
+		assert_eq!(universe.population(), 4);
+
+		universe.clear();
+		assert_eq!(universe.shrink_to_fit(), (0, 0));
+		assert_eq!((universe.width(), universe.height()), (1, 1));
+	}
+
+	#[test]
+	fn test_set_named_rule() {
+		let mut universe = Universe::empty(4, 4);
+		universe.set_named_rule("highlife").unwrap();
+		assert_eq!(universe.rule().to_string(), "B36/S23");
+
+		assert_eq!(universe.set_named_rule("lowlife"), Err(RuleError::UnknownName));
+		assert_eq!(universe.rule().to_string(), "B36/S23");
+	}
+
+	#[test]
+	fn test_subtract() {
+		let square: Vec<_> = (0..3).flat_map(|y| (0..3).map(move |x| (x, y))).collect();
+		let mut universe = Universe::empty(6, 6);
+		universe.place(square.iter().copied(), 2, 2);
+		universe.subtract(&[(1, 1)], 2, 2);
+
+		assert_eq!(universe.population(), 8);
+		assert!(!universe.get_cell(3, 3));
+
+		// subtracting dead cells keeps them dead, offsets wrap around
+		universe.subtract(&[(0, 0), (4, 4)], 2, 2);
+		assert_eq!(universe.population(), 7);
+		assert!(!universe.get_cell(2, 2));
+	}
+
+	#[test]
+	fn test_matches_translated() {
+		let mut universe = Universe::empty(8, 8);
+		universe.place(shape::GLIDER.iter().copied(), 0, 0);
+		let start = Universe::from_coords(8, 8, &universe.live_cells());
+
+		universe.tick();
+		assert_eq!(start.matches_translated(&universe), None);
+		universe.tick_many(3);
+		assert_eq!(start.matches_translated(&universe), Some((-1, -1)));
+		assert_eq!(universe.matches_translated(&start), Some((1, 1)));
+		assert_eq!(start.matches_translated(&start), Some((0, 0)));
+
+		// the glider wraps around the edges, 7 cells up and left is 1 down and right
+		universe.tick_many(24);
+		assert_eq!(start.matches_translated(&universe), Some((1, 1)));
+		assert_eq!(Universe::empty(4, 4).matches_translated(&Universe::empty(4, 4)), Some((0, 0)));
+		assert_eq!(start.matches_translated(&Universe::empty(8, 7)), None);
+	}
+
+	#[test]
+	fn test_settings_strings() {
+		let mut universe = Universe::empty(4, 4);
+		assert_eq!(universe.rule_string(), "B3/S23");
+		assert_eq!(universe.boundary_mode(), "toroidal");
+		assert_eq!(universe.neighborhood(), "moore");
+
+		universe.set_named_rule("highlife").unwrap();
+		universe.set_boundary(Boundary::Dead);
+		universe.set_neighborhood(Neighborhood::VonNeumann);
+		assert_eq!(universe.rule_string(), "B36/S23");
+		assert_eq!(universe.boundary_mode(), "dead");
+		assert_eq!(universe.neighborhood(), "von-neumann");
+
+		universe.set_rule_from_str("b63/s32").unwrap();
+		assert_eq!(universe.rule_string(), "B36/S23");
+	}
+
+	#[test]
+	fn test_count_spaceships() {
+		let mut universe = Universe::empty(16, 16);
+		universe.place(shape::GLIDER.iter().copied(), 2, 2);
+		universe.place(shape::BLOCK.iter().copied(), 11, 11);
+		universe.place(shape::BLINKER.iter().copied(), 10, 3);
+		let cells = universe.live_cells();
+
+		assert_eq!(universe.count_spaceships(), 1);
+		assert_eq!(universe.live_cells(), cells);
+		assert_eq!(universe.generation(), 0);
+
+		universe.place(shape::GLIDER.iter().copied(), 3, 10);
+		assert_eq!(universe.count_spaceships(), 2);
+		assert_eq!(Universe::empty(4, 4).count_spaceships(), 0);
+	}
+
+	#[test]
+	fn test_count_spaceships_identical_still_lifes() {
+		let mut universe = Universe::empty(16, 16);
+		universe.place(shape::BLOCK.iter().copied(), 2, 2);
+		universe.place(shape::BLOCK.iter().copied(), 6, 2);
+		assert_eq!(universe.count_spaceships(), 0);
+
+		universe.clear();
+		universe.place(shape::BLINKER.iter().copied(), 2, 8);
+		universe.place(shape::BLINKER.iter().copied(), 9, 8);
+		assert_eq!(universe.count_spaceships(), 0);
+
+		universe.place(shape::GLIDER.iter().copied(), 10, 12);
+		assert_eq!(universe.count_spaceships(), 1);
+	}
+
+	#[test]
+	fn test_random_interior() {
+		let universe = Universe::random_interior_seeded(12, 10, 3, 1.0, 1);
+		assert_eq!(universe.population(), 6 * 4);
+		for (x, y) in universe.live_cells().chunks_exact(2).map(|xy| (xy[0], xy[1])) {
+			assert!((3..9).contains(&x) && (3..7).contains(&y), "({}, {}) is within the margin", x, y);
+		}
+
+		let universe = Universe::random_interior_seeded(32, 32, 4, 0.5, 2);
+		let border = universe.live_cells().chunks_exact(2).any(|xy| xy.iter().any(|&c| !(4..28).contains(&c)));
+		assert!(!border);
+		assert!(universe.population() > 0);
+		assert_eq!(Universe::random_interior_seeded(6, 6, 3, 1.0, 3).population(), 0);
+	}
+
+	#[test]
+	fn test_debug() {
+		let mut universe = Universe::empty(64, 32);
+		universe.place(shape::GLIDER.iter().copied(), 0, 0);
+		universe.tick();
+		assert_eq!(format!("{:?}", universe), "Universe { width: 64, height: 32, generation: 1, population: 5 }");
+	}
+
+	#[test]
+	fn test_injection() {
+		let mut universe = Universe::empty(8, 8);
+		universe.set_cell(2, 2, true);
+		universe.set_injection(vec![(2, 2), (13, 5)]);
+
+		for _ in 0..4 {
+			universe.tick();
+			assert_eq!(universe.live_cells(), &[2, 2, 5, 5]);
+		}
+		assert!(universe.changed_cells().is_empty());
+
+		universe.clear_injection();
+		universe.tick();
+		assert_eq!(universe.population(), 0);
+		assert_eq!(universe.changed_cells(), &[2, 2, 5, 5]);
+	}
+
+	#[test]
+	fn test_render_activity() {
+		let mut universe = Universe::empty(5, 5);
+		universe.place(shape::BLINKER.iter().copied(), 1, 2);
+		universe.tick();
+
+		let pixels = universe.render_activity(1, 2, 3, 4);
+		let color = |x: usize, y: usize| pixels[(y * 5 + x) * 4 + 3];
+		assert_eq!((color(2, 1), color(2, 3)), (1, 1));
+		assert_eq!((color(1, 2), color(3, 2)), (2, 2));
+		assert_eq!(color(2, 2), 3);
+		assert_eq!(color(0, 0), 4);
+		assert_eq!(pixels.len(), 5 * 5 * 4);
+	}
+
+	#[test]
+	fn test_neighbor_count_2x2() {
+		let mut universe = Universe::empty(2, 2);
+		assert_eq!((universe.prev_x(0), universe.next_x(0)), (1, 1));
+		assert_eq!((universe.prev_y(1), universe.next_y(1)), (0, 0));
+
+		// the neighbors on either side are the same cell, which is only counted once
+		universe.set_cell(1, 1, true);
+		assert_eq!(universe.neighbor_count(0, 0), 1);
+		assert_eq!(universe.neighbor_count(1, 1), 0);
+		universe.place([(0, 0), (1, 0), (0, 1)], 0, 0);
+		assert_eq!(universe.neighbor_count(0, 0), 3);
+
+		universe.set_boundary(Boundary::Dead);
+		assert_eq!(universe.neighbor_count(0, 0), 3);
+		assert_eq!(universe.neighbor_count(1, 1), 3);
+
+		universe.set_neighborhood(Neighborhood::VonNeumann);
+		assert_eq!(universe.neighbor_count(0, 0), 2);
+		universe.set_boundary(Boundary::Toroidal);
+		assert_eq!(universe.neighbor_count(1, 0), 2);
+
+		let universe = Universe::from_coords(1, 3, &[0, 0, 0, 1, 0, 2]);
+		assert_eq!(universe.neighbor_count(0, 1), 2);
+		assert_eq!(universe.neighbor_count(0, 0), 2);
+	}
+
+	#[test]
+	fn test_empty_inputs() {
+		assert_eq!(Universe::from_rle("!"), Err(RleError::Universe(UniverseError::ZeroDimension { width: 0, height: 0 })));
+		assert!(matches!(Universe::from_rle("x = 3, y = 1\n3b!"), Err(RleError::Universe(_))));
+
+		for universe in [
+			Universe::from_string("", 'X'),
+			Universe::from_coords(0, 0, &[]),
+			Universe::from_life106("#Life 1.06\n0 0\n", 0, 0),
+			Universe::glider_gun(0, 0),
+			Universe::checkerboard(0, 1),
+			Universe::stripes(1, 0, 2),
+			Universe::random_with_density_seeded(0, 0, 0.5, 1),
+			Universe::random_seeded(0, 0, 1),
+			Universe::soup(0, 0, "empty"),
+			Universe::empty_with_layout(0, 0, Layout::Tiled),
+		] {
+			assert_eq!((universe.width(), universe.height()), (1, 1));
+		}
+		// the clamped universes can be ticked and indexed
+		let mut universe = Universe::random_seeded(0, 3, 1);
+		assert_eq!((universe.width(), universe.height()), (1, 3));
+		universe.tick();
+		assert!(!Universe::soup(2, 0, "empty").get_cell(5, 5));
+
+		let mut universe = Universe::checkerboard(4, 4);
+		assert_eq!(universe.try_resize(0, 4), Err(UniverseError::ZeroDimension { width: 0, height: 4 }));
+		assert_eq!((universe.width(), universe.height(), universe.population()), (4, 4, 8));
+		assert_eq!(universe.try_resize(2, 2), Ok(()));
+		assert_eq!(universe.population(), 2);
+	}
+
+	#[test]
+	fn test_get_cell() {
+		const CELLS: &[(u32, u32)] = &[(1,2), (2,3), (3,1), (3,2), (3,3)];
+		let mut universe = Universe::empty(6, 6);
+		universe.place(CELLS.iter().copied(), 0, 0);
+
+		for &(x, y) in CELLS {
 			assert!(universe.cells.get(universe.idx(x, y)));
 		}
 	}
+
+	#[test]
+	fn test_random_seeded() {
+		let a = Universe::random_seeded(20, 20, 1234);
+		let b = Universe::random_seeded(20, 20, 1234);
+		assert_eq!(a, b);
+		assert_ne!(a, Universe::random_seeded(20, 20, 4321));
+	}
+
+	#[test]
+	fn test_get_cell_wrapping() {
+		let mut universe = Universe::empty(4, 3);
+		universe.set_cell(2, 1, true);
+
+		assert!(universe.get_cell(2, 1));
+		assert!(universe.get_cell(6, 4));
+		assert!(!universe.get_cell(1, 2));
+	}
+
+	#[test]
+	fn test_toggle_cell() {
+		let mut universe = Universe::empty(4, 4);
+		universe.toggle_cell(1, 2);
+		assert!(universe.cells.get(universe.idx(1, 2)));
+		assert_eq!(universe.population(), 1);
+
+		universe.toggle_cell(1, 2);
+		assert_eq!(universe, Universe::empty(4, 4));
+
+		// out of bounds coordinates wrap
+		universe.set_cell(5, 6, true);
+		assert!(universe.cells.get(universe.idx(1, 2)));
+		universe.set_cell(1, 2, false);
+		assert_eq!(universe, Universe::empty(4, 4));
+	}
+
+	#[test]
+	fn test_population() {
+		let mut universe = Universe::empty(5, 5);
+		assert_eq!(universe.population(), 0);
+
+		universe.place(shape::GLIDER.iter().copied(), 1, 1);
+		assert_eq!(universe.population(), 5);
+
+		// padding bits past the last cell must not be counted
+		universe.cells.set(25, true);
+		universe.cells.set(31, true);
+		assert_eq!(universe.population(), 5);
+	}
 }
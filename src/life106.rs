@@ -0,0 +1,50 @@
+//! Life 1.06 pattern format, see <https://conwaylife.com/wiki/Life_1.06>.
+
+use std::fmt::Write;
+
+/// Header line of the format.
+const HEADER: &str = "#Life 1.06";
+
+/// Parse a Life 1.06 pattern into the coordinates of live cells.
+///
+/// Note: `#` lines and lines that are not a pair of integers are ignored.
+pub fn parse_life106(input: &str) -> Vec<(i64, i64)> {
+	input.lines()
+		.filter(|line| !line.trim_start().starts_with('#'))
+		.filter_map(|line| {
+			let mut coords = line.split_whitespace().map(str::parse::<i64>);
+			match (coords.next(), coords.next(), coords.next()) {
+				(Some(Ok(x)), Some(Ok(y)), None) => Some((x, y)),
+				_ => None,
+			}
+		})
+		.collect()
+}
+
+/// Encode provided live cells as a Life 1.06 pattern.
+pub fn encode_life106<T>(cells: T) -> String
+where T: IntoIterator<Item = (u32, u32)> {
+	let mut result = String::from(HEADER);
+	result.push('\n');
+	for (x, y) in cells {
+		writeln!(result, "{} {}", x, y).unwrap();
+	}
+	result
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_parse() {
+		let input = "#Life 1.06\n#D a comment\n0 -1\n1 0\n-1 1\n  0 1 \n1 1\nnot a cell\n1 2 3\n";
+		assert_eq!(parse_life106(input), &[(0, -1), (1, 0), (-1, 1), (0, 1), (1, 1)]);
+	}
+
+	#[test]
+	fn test_encode() {
+		assert_eq!(encode_life106([(1, 0), (0, 2)]), "#Life 1.06\n1 0\n0 2\n");
+		assert_eq!(encode_life106([]), "#Life 1.06\n");
+	}
+}
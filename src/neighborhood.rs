@@ -0,0 +1,29 @@
+use std::fmt;
+
+use wasm_bindgen::prelude::*;
+
+/// Cells considered to be neighbors of a cell.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Neighborhood {
+	/// The 8 surrounding cells.
+	#[default]
+	Moore,
+	/// The 4 orthogonally adjacent cells.
+	VonNeumann,
+	/// The 24 cells within 2 cells in each direction, as in range 2 Larger than Life rules.
+	///
+	/// Note: rules with counts above 8 are written in the range notation, see `Rule::parse_range`.
+	MooreRange2,
+}
+
+impl fmt::Display for Neighborhood {
+	/// Write the lowercase name of the neighborhood, for example `"von-neumann"`.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Moore => write!(f, "moore"),
+			Self::VonNeumann => write!(f, "von-neumann"),
+			Self::MooreRange2 => write!(f, "moore-range2"),
+		}
+	}
+}
@@ -0,0 +1,107 @@
+//! Bit-parallel neighbor counting, evaluating a row of cells 64 at a time.
+//!
+//! Rows are stored as words where bit `x % 64` of word `x / 64` is the cell at column `x`.
+
+/// Write a copy of the row with every cell moved one column right into `result`, so bit `x` holds cell `x - 1`.
+///
+/// The first cell is the last one of the row when wrapping, dead otherwise.
+pub fn shift_right(row: &[u64], width: u32, wrap: bool, result: &mut [u64]) {
+	for i in 0..row.len() {
+		result[i] = row[i] << 1 | if i > 0 { row[i - 1] >> 63 } else { 0 };
+	}
+	let last = width as usize - 1;
+	if wrap {
+		result[0] |= row[last / 64] >> (last % 64) & 1;
+	}
+	result[last / 64] &= last_word_mask(width);
+}
+
+/// Write a copy of the row with every cell moved one column left into `result`, so bit `x` holds cell `x + 1`.
+///
+/// The last cell is the first one of the row when wrapping, dead otherwise.
+pub fn shift_left(row: &[u64], width: u32, wrap: bool, result: &mut [u64]) {
+	for i in 0..row.len() {
+		result[i] = row[i] >> 1 | row.get(i + 1).map_or(0, |next| next << 63);
+	}
+	let last = width as usize - 1;
+	if wrap {
+		result[last / 64] |= (row[0] & 1) << (last % 64);
+	}
+}
+
+/// Get the mask of bits within the last word of a row that are part of it.
+fn last_word_mask(width: u32) -> u64 {
+	match width % 64 {
+		0 => u64::MAX,
+		bits => !(u64::MAX << bits),
+	}
+}
+
+/// Bit-sliced counter of up to 15 for 64 cells at once.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Counter([u64; 4]);
+
+impl Counter {
+	/// Increment the count of cells whose bits are set.
+	pub fn add(&mut self, bits: u64) {
+		let mut carry = bits;
+		for plane in &mut self.0 {
+			let sum = *plane ^ carry;
+			carry &= *plane;
+			*plane = sum;
+		}
+	}
+
+	/// Get the mask of cells whose count is set in provided mask, where bit `n` stands for a count of `n`.
+	pub fn matching(&self, mask: u32) -> u64 {
+		(0..16)
+			.filter(|n| mask >> n & 1 == 1)
+			.map(|n| self.0.iter()
+				.enumerate()
+				.fold(u64::MAX, |acc, (bit, &plane)| acc & if n >> bit & 1 == 1 { plane } else { !plane }))
+			.fold(0, |acc, matching| acc | matching)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn shifted<F>(shift: F, row: &[u64], width: u32, wrap: bool) -> Vec<u64>
+	where F: Fn(&[u64], u32, bool, &mut [u64]) {
+		let mut result = vec![0; row.len()];
+		shift(row, width, wrap, &mut result);
+		result
+	}
+
+	#[test]
+	fn test_shift() {
+		let row = [0b1000_0011];
+		assert_eq!(shifted(shift_right, &row, 8, true), &[0b0000_0111]);
+		assert_eq!(shifted(shift_right, &row, 8, false), &[0b0000_0110]);
+		assert_eq!(shifted(shift_left, &row, 8, true), &[0b1100_0001]);
+		assert_eq!(shifted(shift_left, &row, 8, false), &[0b0100_0001]);
+	}
+
+	#[test]
+	fn test_shift_across_words() {
+		let row = [1 << 63, 0b10];
+		assert_eq!(shifted(shift_right, &row, 66, false), &[0, 0b1]);
+		assert_eq!(shifted(shift_right, &row, 66, true), &[1, 0b1]);
+		assert_eq!(shifted(shift_left, &row, 66, false), &[1 << 62, 0b1]);
+		assert_eq!(shifted(shift_left, &[1, 0], 66, true), &[0, 0b10]);
+	}
+
+	#[test]
+	fn test_counter() {
+		let mut counter = Counter::default();
+		for bits in [0b111, 0b110, 0b100, 0b100] {
+			counter.add(bits);
+		}
+		assert_eq!(counter.matching(1 << 1), 0b001);
+		assert_eq!(counter.matching(1 << 2), 0b010);
+		assert_eq!(counter.matching(1 << 4), 0b100);
+		assert_eq!(counter.matching(1 << 0 | 1 << 4), !0b011);
+		assert_eq!(counter.matching(0), 0);
+	}
+}
@@ -0,0 +1,73 @@
+use wasm_bindgen::prelude::*;
+
+use crate::bitstore::BitStore;
+
+/// Standalone rectangle of cells, for example copied from a universe to be pasted elsewhere.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Pattern {
+	width: u32,
+	height: u32,
+	/// Cells in row-major order.
+	cells: BitStore,
+}
+
+impl Pattern {
+	/// Create a pattern from row-major cells.
+	///
+	/// Note: the cells are expected to hold exactly `width * height` bits.
+	pub(crate) fn from_cells(width: u32, height: u32, cells: BitStore) -> Self {
+		Self { width, height, cells }
+	}
+
+	/// Create a pattern with cells alive where provided function returns true.
+	pub fn from_fn<F>(width: u32, height: u32, alive: F) -> Self
+	where F: Fn(u32, u32) -> bool {
+		let mut cells = BitStore::empty((width * height) as usize);
+		for y in 0..height {
+			for x in 0..width {
+				cells.set((y * width + x) as usize, alive(x, y));
+			}
+		}
+		Self::from_cells(width, height, cells)
+	}
+}
+
+#[wasm_bindgen]
+impl Pattern {
+	pub fn width(&self) -> u32 {
+		self.width
+	}
+
+	pub fn height(&self) -> u32 {
+		self.height
+	}
+
+	/// Test whether the cell at provided coordinates within the pattern is alive.
+	///
+	/// Panics if the coordinates are outside the pattern.
+	pub fn get(&self, x: u32, y: u32) -> bool {
+		assert!(x < self.width && y < self.height, "coordinates should be within the pattern");
+		self.cells.get((y * self.width + x) as usize)
+	}
+
+	/// Get the number of living cells in the pattern.
+	pub fn population(&self) -> u32 {
+		self.cells.count_ones() as u32
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_from_fn() {
+		let pattern = Pattern::from_fn(3, 2, |x, y| x == y);
+		assert!(pattern.get(0, 0));
+		assert!(pattern.get(1, 1));
+		assert!(!pattern.get(1, 0));
+		assert_eq!(pattern.population(), 2);
+	}
+}
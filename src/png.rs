@@ -0,0 +1,58 @@
+//! PNG export of universes, enabled with the `png` feature.
+
+use wasm_bindgen::prelude::*;
+
+use crate::encode::{scale_rgba, EncodeError};
+use crate::Universe;
+
+impl From<png::EncodingError> for EncodeError {
+	fn from(err: png::EncodingError) -> Self {
+		Self::Encoder(err.to_string())
+	}
+}
+
+#[wasm_bindgen]
+impl Universe {
+	/// Encode the universe as a PNG image with each cell drawn as a `scale` by `scale` square.
+	///
+	/// The colors are packed as `0xRRGGBBAA`, same as `render_rgba`.
+	pub fn to_png(&self, scale: u32, alive: u32, dead: u32) -> Result<Vec<u8>, EncodeError> {
+		let (width, height, pixels) = scale_rgba(&self.render_rgba(alive, dead), self.width, self.height, scale)?;
+
+		let mut bytes = Vec::new();
+		let mut encoder = png::Encoder::new(&mut bytes, width, height);
+		encoder.set_color(png::ColorType::Rgba);
+		encoder.set_depth(png::BitDepth::Eight);
+		let mut writer = encoder.write_header()?;
+		writer.write_image_data(&pixels)?;
+		writer.finish()?;
+		Ok(bytes)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_to_png() {
+		let mut universe = Universe::empty(4, 3);
+		universe.set_cell(1, 2, true);
+		let bytes = universe.to_png(2, 0xFF00_00FF, 0x0000_00FF).unwrap();
+
+		let decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+		let mut reader = decoder.read_info().unwrap();
+		let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+		let info = reader.next_frame(&mut pixels).unwrap();
+
+		assert_eq!((info.width, info.height), (8, 6));
+		let pixel = |x: usize, y: usize| &pixels[(y * 8 + x) * 4..][..4];
+		assert_eq!(pixel(3, 5), &[0xFF, 0, 0, 0xFF]);
+		assert_eq!(pixel(4, 5), &[0, 0, 0, 0xFF]);
+	}
+
+	#[test]
+	fn test_to_png_zero_scale() {
+		assert_eq!(Universe::empty(4, 3).to_png(0, 0, 0), Err(EncodeError::ZeroScale));
+	}
+}
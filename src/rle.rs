@@ -0,0 +1,224 @@
+//! Run Length Encoded pattern format, see <https://conwaylife.com/wiki/Run_Length_Encoded>.
+
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+use crate::error::UniverseError;
+use crate::rule::Rule;
+
+/// Maximum length of an encoded line, as recommended by the format.
+const MAX_LINE_LENGTH: usize = 70;
+
+/// Reasons an RLE pattern may fail to parse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RleError {
+	/// Encountered a character that is not a valid RLE token.
+	UnexpectedChar(char),
+	/// A run count is too large or is not followed by a tag.
+	InvalidCount,
+	/// The pattern does not fit in a universe, for example because it has no living cells.
+	Universe(UniverseError),
+}
+
+impl fmt::Display for RleError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::UnexpectedChar(c) => write!(f, "unexpected character '{}' in RLE pattern", c),
+			Self::InvalidCount => write!(f, "invalid run count in RLE pattern"),
+			Self::Universe(err) => write!(f, "{}", err),
+		}
+	}
+}
+
+impl std::error::Error for RleError {}
+
+impl From<UniverseError> for RleError {
+	fn from(err: UniverseError) -> Self {
+		Self::Universe(err)
+	}
+}
+
+impl From<RleError> for JsValue {
+	fn from(err: RleError) -> Self {
+		JsValue::from_str(&err.to_string())
+	}
+}
+
+/// Coordinates of live cells.
+type Cells = Vec<(u32, u32)>;
+
+/// Check whether provided line is the `x = .., y = ..` header.
+fn is_header(line: &str) -> bool {
+	let mut chars = line.chars().skip_while(|c| c.is_whitespace());
+	chars.next() == Some('x') && chars.find(|c| !c.is_whitespace()) == Some('=')
+}
+
+/// Parse an RLE pattern.
+///
+/// Returns the coordinates of live cells along with the width and height of their bounding box,
+/// which is anchored at the origin of the pattern.
+/// Note: `#` comment lines and the header line are ignored.
+pub fn parse_rle(input: &str) -> Result<(Cells, u32, u32), RleError> {
+	let mut cells = Vec::new();
+	let (mut width, mut height) = (0, 0);
+	let (mut x, mut y) = (0u32, 0u32);
+	let mut count: Option<u32> = None;
+
+	let body = input.lines()
+		.filter(|line| !line.trim_start().starts_with('#') && !is_header(line))
+		.flat_map(|line| line.chars());
+
+	for c in body {
+		if let Some(digit) = c.to_digit(10) {
+			let run = count.unwrap_or(0).checked_mul(10).and_then(|run| run.checked_add(digit));
+			count = Some(run.ok_or(RleError::InvalidCount)?);
+			continue;
+		}
+
+		let run = count.take().unwrap_or(1);
+		match c {
+			'b' | '.' => x = x.checked_add(run).ok_or(RleError::InvalidCount)?,
+			'o' => {
+				let end = x.checked_add(run).ok_or(RleError::InvalidCount)?;
+				cells.extend((x..end).map(|x| (x, y)));
+				if run > 0 {
+					width = width.max(end);
+					height = y + 1;
+				}
+				x = end;
+			},
+			'$' => {
+				x = 0;
+				y = y.checked_add(run).ok_or(RleError::InvalidCount)?;
+			},
+			'!' => return Ok((cells, width, height)),
+			c if c.is_whitespace() && run == 1 => (),
+			_ if run != 1 => return Err(RleError::InvalidCount),
+			c => return Err(RleError::UnexpectedChar(c)),
+		}
+	}
+
+	if count.is_some() {
+		return Err(RleError::InvalidCount);
+	}
+	Ok((cells, width, height))
+}
+
+/// Writer of RLE tokens that keeps lines within the recommended length.
+struct RleWriter {
+	output: String,
+	line_length: usize,
+}
+
+impl RleWriter {
+	fn push(&mut self, count: u32, tag: char) {
+		let token = match count {
+			0 => return,
+			1 => tag.to_string(),
+			_ => format!("{}{}", count, tag),
+		};
+		if self.line_length + token.len() > MAX_LINE_LENGTH {
+			self.output.push('\n');
+			self.line_length = 0;
+		}
+		self.line_length += token.len();
+		self.output.push_str(&token);
+	}
+}
+
+/// Encode a pattern of provided size as RLE.
+///
+/// Trailing dead cells of rows and trailing empty rows are omitted.
+pub fn encode_rle<F>(width: u32, height: u32, rule: &Rule, is_alive: F) -> String
+where F: Fn(u32, u32) -> bool {
+	let mut writer = RleWriter {
+		output: format!("x = {}, y = {}, rule = {}\n", width, height, rule),
+		line_length: 0,
+	};
+	let mut pending_rows = 0;
+
+	for y in 0..height {
+		let mut runs = Vec::new();
+		let mut x = 0;
+		while x < width {
+			let alive = is_alive(x, y);
+			let start = x;
+			while x < width && is_alive(x, y) == alive {
+				x += 1;
+			}
+			runs.push((x - start, if alive { 'o' } else { 'b' }));
+		}
+		if let Some((_, 'b')) = runs.last() {
+			runs.pop();
+		}
+
+		if !runs.is_empty() {
+			writer.push(pending_rows, '$');
+			pending_rows = 0;
+			runs.into_iter().for_each(|(count, tag)| writer.push(count, tag));
+		}
+		pending_rows += 1;
+	}
+
+	writer.push(1, '!');
+	writer.output
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_parse_glider() {
+		let rle = "#N Glider\n#C A comment\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!";
+		let (cells, width, height) = parse_rle(rle).unwrap();
+
+		assert_eq!(cells, &[(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+		assert_eq!((width, height), (3, 3));
+	}
+
+	#[test]
+	fn test_parse_blinker() {
+		let (cells, width, height) = parse_rle("x = 3, y = 1\n3o!").unwrap();
+
+		assert_eq!(cells, &[(0, 0), (1, 0), (2, 0)]);
+		assert_eq!((width, height), (3, 1));
+	}
+
+	#[test]
+	fn test_parse_multiline() {
+		let (cells, width, height) = parse_rle("2o$\n\n2$\n2b2o!ignored").unwrap();
+
+		assert_eq!(cells, &[(0, 0), (1, 0), (2, 3), (3, 3)]);
+		assert_eq!((width, height), (4, 4));
+	}
+
+	#[test]
+	fn test_encode() {
+		let cells = [(1, 1), (2, 1), (3, 1), (0, 3)];
+		let rle = encode_rle(5, 5, &Rule::CONWAY, |x, y| cells.contains(&(x, y)));
+		assert_eq!(rle, "x = 5, y = 5, rule = B3/S23\n$b3o2$o!");
+
+		let empty = encode_rle(3, 3, &Rule::CONWAY, |_, _| false);
+		assert_eq!(empty, "x = 3, y = 3, rule = B3/S23\n!");
+	}
+
+	#[test]
+	fn test_encode_long_lines() {
+		let rle = encode_rle(200, 1, &Rule::CONWAY, |x, _| x % 2 == 0);
+		let (header, body) = rle.split_once('\n').unwrap();
+
+		assert_eq!(header, "x = 200, y = 1, rule = B3/S23");
+		assert!(body.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+		assert_eq!(parse_rle(&rle).unwrap().0.len(), 100);
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		assert_eq!(parse_rle("bo$2x!"), Err(RleError::InvalidCount));
+		assert_eq!(parse_rle("bo$x!"), Err(RleError::UnexpectedChar('x')));
+		assert_eq!(parse_rle("bo$3"), Err(RleError::InvalidCount));
+		assert_eq!(parse_rle("99999999999o!"), Err(RleError::InvalidCount));
+	}
+}
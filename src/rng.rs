@@ -0,0 +1,93 @@
+#[cfg(target_arch = "wasm32")]
+use js_sys::Math;
+
+use crate::utils::fnv1a;
+
+/// Small deterministic pseudo-random number generator (SplitMix64).
+///
+/// Unlike `Math::random()` it produces identical sequences in native tests and wasm.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+	/// Create a generator with the provided seed.
+	pub fn new(seed: u64) -> Self {
+		Self(seed)
+	}
+
+	/// Create a generator seeded from `Math::random()`.
+	#[cfg(target_arch = "wasm32")]
+	pub fn from_entropy() -> Self {
+		// Math::random() only provides 52 bits of randomness, so combine 2 calls
+		let hi = (Math::random() * u32::MAX as f64) as u64;
+		let lo = (Math::random() * u32::MAX as f64) as u64;
+		Self::new(hi << 32 | lo)
+	}
+
+	/// Create a generator seeded from the randomly keyed hasher of the standard library,
+	/// `Math::random()` is only available in the browser.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn from_entropy() -> Self {
+		use std::collections::hash_map::RandomState;
+		use std::hash::BuildHasher;
+		Self::new(RandomState::new().hash_one(0u8))
+	}
+
+	/// Create a generator seeded from a hash of the provided label.
+	///
+	/// Uses FNV-1a, so the same label yields the same sequence on every platform and version.
+	pub fn from_label(label: &str) -> Self {
+		Self::new(fnv1a(label.bytes()))
+	}
+
+	/// Get the next 64 random bits.
+	pub fn next_u64(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^ (z >> 31)
+	}
+
+	/// Get a random number in the [0, 1) range.
+	pub fn next_f64(&mut self) -> f64 {
+		// use the top 53 bits, which is the precision of f64
+		(self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+	}
+}
+
+#[test]
+fn test_from_entropy() {
+	let (mut a, mut b) = (Rng::from_entropy(), Rng::from_entropy());
+	assert_ne!(a.next_u64(), b.next_u64());
+}
+
+#[test]
+fn test_next_f64() {
+	let mut rng = Rng::new(1);
+	for _ in 0..1000 {
+		let x = rng.next_f64();
+		assert!((0.0..1.0).contains(&x));
+	}
+}
+
+#[test]
+fn test_rng_deterministic() {
+	let mut a = Rng::new(42);
+	let mut b = Rng::new(42);
+	let mut c = Rng::new(43);
+
+	for _ in 0..8 {
+		let x = a.next_u64();
+		assert_eq!(x, b.next_u64());
+		assert_ne!(x, c.next_u64());
+	}
+}
+
+#[test]
+fn test_from_label() {
+	assert_eq!(Rng::from_label("").0, 0xCBF2_9CE4_8422_2325);
+	assert_eq!(Rng::from_label("a").0, 0xAF63_DC4C_8601_EC8C);
+	assert_eq!(Rng::from_label("soup").next_u64(), Rng::from_label("soup").next_u64());
+	assert_ne!(Rng::from_label("soup").next_u64(), Rng::from_label("soup2").next_u64());
+}
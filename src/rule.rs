@@ -0,0 +1,330 @@
+use std::fmt;
+use std::str::FromStr;
+
+use wasm_bindgen::JsValue;
+
+/// Birth and survival conditions of a life-like cellular automaton.
+///
+/// Each bit of a mask corresponds to a neighbor count, so `1 << 3` in `birth`
+/// means dead cells with 3 living neighbors come alive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rule {
+	pub birth: u32,
+	pub survival: u32,
+}
+
+impl Rule {
+	/// Conway's original B3/S23 rule.
+	pub const CONWAY: Self = Self { birth: 1 << 3, survival: 1 << 2 | 1 << 3 };
+
+	/// Well-known rules by name, see `Rule::from_name`.
+	pub const NAMED: &'static [(&'static str, Self)] = &[
+		("conway", Self::CONWAY),
+		("highlife", Self { birth: 1 << 3 | 1 << 6, survival: 1 << 2 | 1 << 3 }),
+		("daynight", Self { birth: 1 << 3 | 1 << 6 | 1 << 7 | 1 << 8, survival: 1 << 3 | 1 << 4 | 1 << 6 | 1 << 7 | 1 << 8 }),
+		("seeds", Self { birth: 1 << 2, survival: 0 }),
+		("replicator", Self { birth: 1 << 1 | 1 << 3 | 1 << 5 | 1 << 7, survival: 1 << 1 | 1 << 3 | 1 << 5 | 1 << 7 }),
+		("life-without-death", Self { birth: 1 << 3, survival: 0x1FF }),
+	];
+
+	/// Get the next state of a cell with provided state and number of living neighbors.
+	///
+	/// Note: counts that do not fit in the masks never satisfy the rule.
+	pub fn next_state(&self, alive: bool, neighbors: u32) -> bool {
+		let mask = if alive { self.survival } else { self.birth };
+		mask.checked_shr(neighbors).is_some_and(|mask| mask & 1 == 1)
+	}
+}
+
+impl Default for Rule {
+	fn default() -> Self {
+		Self::CONWAY
+	}
+}
+
+/// Largest neighbor count of the range notation, the number of cells within 2 cells of another.
+const MAX_RANGE_COUNT: u32 = 24;
+
+/// Write the neighbor counts of provided mask as digits.
+fn write_mask(f: &mut fmt::Formatter, mask: u32) -> fmt::Result {
+	for n in (0..=8).filter(|n| mask >> n & 1 == 1) {
+		write!(f, "{}", n)?;
+	}
+	Ok(())
+}
+
+/// Write the neighbor counts of provided mask as a part with given prefix for each run of consecutive counts.
+fn write_ranges(f: &mut fmt::Formatter, mask: u32, prefix: char) -> fmt::Result {
+	let mut n = 0;
+	while n < 32 {
+		if mask >> n & 1 == 0 {
+			n += 1;
+			continue;
+		}
+		let end = n + (mask >> n).trailing_ones() - 1;
+		write!(f, ",{}{}..{}", prefix, n, end)?;
+		n = end + 1;
+	}
+	Ok(())
+}
+
+impl fmt::Display for Rule {
+	/// Write the rule in the `B{digits}/S{digits}` form, for example `"B3/S23"`.
+	///
+	/// Rules with counts above 8 are written in the range notation, see `Rule::parse_range`.
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if (self.birth | self.survival) >> 9 != 0 {
+			write!(f, "R2,C0,M0")?;
+			write_ranges(f, self.survival, 'S')?;
+			write_ranges(f, self.birth, 'B')?;
+			return write!(f, ",NM");
+		}
+		write!(f, "B")?;
+		write_mask(f, self.birth)?;
+		write!(f, "/S")?;
+		write_mask(f, self.survival)
+	}
+}
+
+/// Reasons a rule string may fail to parse.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleError {
+	/// The rule does not follow the `B{digits}/S{digits}` form.
+	Malformed,
+	/// A part of the rule does not start with the provided prefix.
+	MissingPrefix(char),
+	/// Encountered a character that is not a neighbor count.
+	UnexpectedChar(char),
+	/// The provided neighbor count is not in the 0..=8 range.
+	DigitOutOfRange(char),
+	/// The provided neighbor count of the range notation is larger than the neighborhood.
+	CountOutOfRange(u32),
+	/// The range notation uses a neighborhood other than the provided one.
+	UnsupportedNeighborhood(String),
+	/// The provided neighbor count is listed more than once.
+	DuplicateDigit(char),
+	/// A multi-state rule needs at least the dead and alive states.
+	TooFewStates(u8),
+	/// The name does not match any of the `Rule::NAMED` rules.
+	UnknownName,
+	/// The rule births cells without living neighbors, which an unbounded universe can not hold.
+	BirthWithoutNeighbors,
+}
+
+impl fmt::Display for RuleError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Malformed => write!(f, "expected a rule in B{{digits}}/S{{digits}} form"),
+			Self::MissingPrefix(prefix) => write!(f, "expected rule part to start with '{}'", prefix),
+			Self::UnexpectedChar(c) => write!(f, "unexpected character '{}' in rule", c),
+			Self::DigitOutOfRange(c) => write!(f, "neighbor count '{}' is not in 0..=8 range", c),
+			Self::CountOutOfRange(n) => write!(f, "neighbor count {} is not in 0..={} range", n, MAX_RANGE_COUNT),
+			Self::UnsupportedNeighborhood(part) => write!(f, "rule part '{}' is not supported by the neighborhood", part),
+			Self::DuplicateDigit(c) => write!(f, "neighbor count '{}' is listed more than once", c),
+			Self::TooFewStates(states) => write!(f, "rule has {} states, but needs at least 2", states),
+			Self::BirthWithoutNeighbors => write!(f, "rule births cells without living neighbors"),
+			Self::UnknownName => {
+				write!(f, "unknown rule name, expected one of")?;
+				for (i, (name, _)) in Rule::NAMED.iter().enumerate() {
+					write!(f, "{} \"{}\"", if i == 0 { "" } else { "," }, name)?;
+				}
+				Ok(())
+			},
+		}
+	}
+}
+
+impl std::error::Error for RuleError {}
+
+impl From<RuleError> for JsValue {
+	fn from(err: RuleError) -> Self {
+		JsValue::from_str(&err.to_string())
+	}
+}
+
+/// Parse the neighbor counts following the provided prefix into a mask.
+///
+/// Note: surrounding whitespace is ignored.
+fn parse_mask(part: &str, prefix: char) -> Result<u32, RuleError> {
+	let mut chars = part.trim().chars();
+	match chars.next() {
+		Some(c) if c.eq_ignore_ascii_case(&prefix) => (),
+		_ => return Err(RuleError::MissingPrefix(prefix)),
+	}
+
+	chars.try_fold(0, |mask, c| match c.to_digit(10) {
+		Some(n) if n > 8 => Err(RuleError::DigitOutOfRange(c)),
+		Some(n) if mask >> n & 1 == 1 => Err(RuleError::DuplicateDigit(c)),
+		Some(n) => Ok(mask | 1 << n),
+		None => Err(RuleError::UnexpectedChar(c)),
+	})
+}
+
+impl Rule {
+	/// Parse a rule in the `B{digits}/S{digits}` form, for example `"B3/S23"`.
+	///
+	/// The digits may be in any order and the parts may be surrounded by whitespace,
+	/// the `Display` implementation writes the canonical form.
+	pub fn parse(s: &str) -> Result<Rule, RuleError> {
+		let (birth, survival) = s.split_once('/').ok_or(RuleError::Malformed)?;
+		Ok(Self {
+			birth: parse_mask(birth, 'B')?,
+			survival: parse_mask(survival, 'S')?,
+		})
+	}
+}
+
+/// Parse a neighbor count or an inclusive `{min}..{max}` range of them of the range notation into a mask.
+///
+/// Note: an empty range has no counts.
+fn parse_range_mask(range: &str) -> Result<u32, RuleError> {
+	if range.is_empty() {
+		return Ok(0);
+	}
+	let count = |s: &str| match s.parse::<u32>() {
+		Ok(n) if n > MAX_RANGE_COUNT => Err(RuleError::CountOutOfRange(n)),
+		Ok(n) => Ok(n),
+		Err(_) => Err(RuleError::Malformed),
+	};
+	let (min, max) = match range.split_once("..") {
+		Some((min, max)) => (count(min)?, count(max)?),
+		None => (count(range)?, count(range)?),
+	};
+	Ok((min..=max).fold(0, |mask, n| mask | 1 << n))
+}
+
+impl Rule {
+	/// Parse a rule in the Larger than Life range notation, for example `"R2,C0,M0,S7..12,B9..11,NM"`.
+	///
+	/// The neighborhood has to be the range 2 Moore one, `R2` and `NM`.
+	/// `M1` counts living cells as their own neighbors, the `S` and `B` parts may be repeated to list several ranges.
+	/// Note: only 2 states are supported, so the `C` part has to be 0 or 2.
+	pub fn parse_range(s: &str) -> Result<Rule, RuleError> {
+		let mut rule = Self { birth: 0, survival: 0 };
+		let (mut has_radius, mut middle) = (false, false);
+		for part in s.trim().split(',').map(str::trim) {
+			let mut chars = part.chars();
+			let (prefix, value) = match chars.next() {
+				Some(prefix) => (prefix.to_ascii_uppercase(), chars.as_str()),
+				None => return Err(RuleError::Malformed),
+			};
+			match (prefix, value) {
+				('R', "2") => has_radius = true,
+				('C', "0" | "2") | ('M', "0") | ('N', "M" | "m") => (),
+				('M', "1") => middle = true,
+				('S', range) => rule.survival |= parse_range_mask(range)?,
+				('B', range) => rule.birth |= parse_range_mask(range)?,
+				('R' | 'C' | 'N', _) => return Err(RuleError::UnsupportedNeighborhood(part.to_string())),
+				_ => return Err(RuleError::Malformed),
+			}
+		}
+		if !has_radius {
+			return Err(RuleError::MissingPrefix('R'));
+		}
+		if middle {
+			// the count of a living cell includes itself
+			rule.survival >>= 1;
+		}
+		Ok(rule)
+	}
+}
+
+impl Rule {
+	/// Get one of the `NAMED` rules, ignoring case.
+	pub fn from_name(name: &str) -> Result<Rule, RuleError> {
+		Self::NAMED.iter()
+			.find(|(known, _)| known.eq_ignore_ascii_case(name))
+			.map(|&(_, rule)| rule)
+			.ok_or(RuleError::UnknownName)
+	}
+}
+
+impl FromStr for Rule {
+	type Err = RuleError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_parse_rule() {
+		assert_eq!("B3/S23".parse(), Ok(Rule::CONWAY));
+		assert_eq!("b36/s23".parse(), Ok(Rule { birth: 1 << 3 | 1 << 6, survival: 1 << 2 | 1 << 3 }));
+		assert_eq!("B2/S".parse(), Ok(Rule { birth: 1 << 2, survival: 0 }));
+
+		assert_eq!("B3S23".parse::<Rule>(), Err(RuleError::Malformed));
+		assert_eq!("S23/B3".parse::<Rule>(), Err(RuleError::MissingPrefix('B')));
+		assert_eq!("B9/S23".parse::<Rule>(), Err(RuleError::DigitOutOfRange('9')));
+	}
+
+	#[test]
+	fn test_parse_detailed_errors() {
+		assert_eq!(Rule::parse("B9/S2"), Err(RuleError::DigitOutOfRange('9')));
+		assert_eq!(Rule::parse("B3/23"), Err(RuleError::MissingPrefix('S')));
+		assert_eq!(Rule::parse("B3/S2x"), Err(RuleError::UnexpectedChar('x')));
+		assert_eq!(Rule::parse("B33/S23"), Err(RuleError::DuplicateDigit('3')));
+		assert_eq!(Rule::parse("B3/S2 3"), Err(RuleError::UnexpectedChar(' ')));
+	}
+
+	#[test]
+	fn test_parse_whitespace() {
+		assert_eq!(Rule::parse("B3 / S23"), Ok(Rule::CONWAY));
+		assert_eq!(Rule::parse(" B3/S32\n"), Ok(Rule::CONWAY));
+		assert_eq!(Rule::parse("B3 / S32").unwrap().to_string(), "B3/S23");
+	}
+
+	#[test]
+	fn test_display_rule() {
+		assert_eq!(Rule::CONWAY.to_string(), "B3/S23");
+		assert_eq!("b63/s32".parse::<Rule>().unwrap().to_string(), "B36/S23");
+		assert_eq!("B2/S".parse::<Rule>().unwrap().to_string(), "B2/S");
+	}
+
+	#[test]
+	fn test_from_name() {
+		assert_eq!(Rule::from_name("conway"), Ok(Rule::CONWAY));
+		assert_eq!(Rule::from_name("HighLife").unwrap().to_string(), "B36/S23");
+		assert_eq!(Rule::from_name("daynight").unwrap().to_string(), "B3678/S34678");
+		assert_eq!(Rule::from_name("life-without-death").unwrap().to_string(), "B3/S012345678");
+		assert_eq!(Rule::from_name("lfie"), Err(RuleError::UnknownName));
+		assert!(RuleError::UnknownName.to_string().ends_with("\"replicator\", \"life-without-death\""));
+	}
+
+	#[test]
+	fn test_next_state() {
+		let rule = Rule::CONWAY;
+		assert!(rule.next_state(false, 3));
+		assert!(!rule.next_state(false, 2));
+		assert!(rule.next_state(true, 2));
+		assert!(!rule.next_state(true, 4));
+		assert!(!Rule { birth: u32::MAX, survival: u32::MAX }.next_state(true, 32));
+	}
+
+	#[test]
+	fn test_parse_range() {
+		// Larger than Life rule with counts beyond 8
+		let rule = Rule::parse_range("R2,C0,M0,S7..12,B9..11,NM").unwrap();
+		assert!(rule.next_state(false, 10));
+		assert!(!rule.next_state(false, 12));
+		assert!(rule.next_state(true, 12));
+		assert_eq!(rule.to_string(), "R2,C0,M0,S7..12,B9..11,NM");
+		assert_eq!(Rule::parse_range(&rule.to_string()), Ok(rule));
+
+		// living cells count themselves with M1
+		let rule = Rule::parse_range("r2, m1, s3..5, s20, b4").unwrap();
+		assert_eq!(rule, Rule { birth: 1 << 4, survival: 1 << 2 | 1 << 3 | 1 << 4 | 1 << 19 });
+		assert_eq!(Rule::parse_range("R2,B3,S2..3").unwrap().to_string(), "B3/S23");
+		assert_eq!(Rule::parse_range("R2,S,B"), Ok(Rule { birth: 0, survival: 0 }));
+
+		assert_eq!(Rule::parse_range("R2,S3..25,B3"), Err(RuleError::CountOutOfRange(25)));
+		assert_eq!(Rule::parse_range("R5,C0,M1,S34..58,B34..45,NM"), Err(RuleError::UnsupportedNeighborhood("R5".to_string())));
+		assert_eq!(Rule::parse_range("R2,S3,B3,NN"), Err(RuleError::UnsupportedNeighborhood("NN".to_string())));
+		assert_eq!(Rule::parse_range("S3..5,B4"), Err(RuleError::MissingPrefix('R')));
+		assert_eq!(Rule::parse_range("R2,S3..x,B4"), Err(RuleError::Malformed));
+	}
+}
@@ -0,0 +1,134 @@
+//! `serde` support for universes, enabled with the `serde` feature.
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{BitStore, Pattern, Universe};
+
+/// Serialized form of a universe.
+#[derive(Serialize)]
+struct UniverseRef<'a> {
+	width: u32,
+	height: u32,
+	generation: u64,
+	cells: &'a [u8],
+}
+
+/// Deserialized form of a universe, validated before conversion.
+#[derive(Deserialize)]
+struct UniverseData {
+	width: u32,
+	height: u32,
+	#[serde(default)]
+	generation: u64,
+	cells: Vec<u8>,
+}
+
+/// Deserialized form of a pattern, validated before conversion.
+#[derive(Deserialize)]
+struct PatternData {
+	width: u32,
+	height: u32,
+	cells: BitStore,
+}
+
+impl Serialize for Universe {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		UniverseRef {
+			width: self.width,
+			height: self.height,
+			generation: self.generation,
+			cells: self.row_major_cells().as_bytes(),
+		}.serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for Universe {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let data = UniverseData::deserialize(deserializer)?;
+		let len = data.width.checked_mul(data.height)
+			.filter(|&len| len > 0)
+			.ok_or_else(|| D::Error::custom("invalid universe dimensions"))? as usize;
+		if data.cells.len() != len.div_ceil(8) {
+			return Err(D::Error::custom(format!(
+				"expected {} bytes of cells, got {}",
+				len.div_ceil(8),
+				data.cells.len(),
+			)));
+		}
+
+		let mut cells = BitStore::from_bytes(data.cells);
+		cells.trim_padding(len);
+		let mut universe = Universe::from_cells(data.width, data.height, cells);
+		universe.generation = data.generation;
+		Ok(universe)
+	}
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let mut data = PatternData::deserialize(deserializer)?;
+		let len = data.width.checked_mul(data.height)
+			.filter(|&len| len > 0)
+			.ok_or_else(|| D::Error::custom("invalid pattern dimensions"))? as usize;
+		if data.cells.valid_bits() != len || data.cells.size() != len.div_ceil(8) {
+			return Err(D::Error::custom(format!(
+				"expected {} cells in {} bytes, got {} in {}",
+				len,
+				len.div_ceil(8),
+				data.cells.valid_bits(),
+				data.cells.size(),
+			)));
+		}
+
+		data.cells.trim_padding(len);
+		Ok(Pattern::from_cells(data.width, data.height, data.cells))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_json_round_trip() {
+		let mut universe = Universe::random_seeded(7, 5, 3);
+		universe.tick();
+
+		let json = serde_json::to_string(&universe).unwrap();
+		assert!(json.starts_with(r#"{"width":7,"height":5,"generation":1,"cells":["#));
+
+		let restored: Universe = serde_json::from_str(&json).unwrap();
+		assert_eq!(restored, universe);
+		assert_eq!(restored.generation(), 1);
+	}
+
+	#[test]
+	fn test_json_invalid() {
+		let json = r#"{"width":7,"height":5,"cells":[0,0,0]}"#;
+		assert!(serde_json::from_str::<Universe>(json).is_err());
+	}
+
+	#[test]
+	fn test_pattern_json_round_trip() {
+		let pattern = Universe::random_seeded(7, 5, 3).copy_region(1, 1, 4, 3);
+		let json = serde_json::to_string(&pattern).unwrap();
+		let restored: Pattern = serde_json::from_str(&json).unwrap();
+		assert_eq!(restored, pattern);
+	}
+
+	#[test]
+	fn test_pattern_json_invalid() {
+		let json = r#"{"width":4,"height":4,"cells":{"bytes":[],"valid_bits":0}}"#;
+		assert!(serde_json::from_str::<Pattern>(json).is_err());
+		let json = r#"{"width":2,"height":2,"cells":{"bytes":[15],"valid_bits":8}}"#;
+		assert!(serde_json::from_str::<Pattern>(json).is_err());
+		let json = r#"{"width":0,"height":4,"cells":{"bytes":[],"valid_bits":0}}"#;
+		assert!(serde_json::from_str::<Pattern>(json).is_err());
+
+		let json = r#"{"width":2,"height":2,"cells":{"bytes":[9],"valid_bits":4}}"#;
+		let pattern: Pattern = serde_json::from_str(json).unwrap();
+		assert!(pattern.get(0, 0) && pattern.get(1, 1));
+		assert_eq!(pattern.population(), 2);
+	}
+}
@@ -1,31 +1,312 @@
-use js_sys::Math;
+use crate::rng::Rng;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Transformation {
 	Identity,
 	RotateLeft,
 	RotateRight,
+	Rotate180,
+	/// Reflect through the center, the same as `Rotate180`.
 	Reflect,
+	/// Mirror left to right.
+	ReflectHorizontal,
+	/// Mirror top to bottom.
+	ReflectVertical,
+	/// Mirror along the main diagonal, swapping x and y.
+	TransposeMain,
+	/// Mirror along the anti-diagonal.
+	TransposeAnti,
 }
 
 impl Transformation {
+	/// All of the distinct transformations, `Reflect` is left out as it is the same as `Rotate180`.
+	pub const ALL: &'static [Self] = &[
+		Self::Identity,
+		Self::RotateLeft,
+		Self::RotateRight,
+		Self::Rotate180,
+		Self::ReflectHorizontal,
+		Self::ReflectVertical,
+		Self::TransposeMain,
+		Self::TransposeAnti,
+	];
+
+	/// Pick a transformation uniformly at random.
 	pub fn random() -> Self {
-		match Math::random() {
-			x if x < 0.25 => Self::Identity,
-			x if x < 0.5 => Self::RotateLeft,
-			x if x < 0.75 => Self::RotateRight,
-			_ => Self::Reflect,
+		Self::ALL[(Rng::from_entropy().next_f64() * Self::ALL.len() as f64) as usize]
+	}
+
+	/// Get the linear part of the transformation as a row-major matrix.
+	fn matrix(self) -> [[i8; 2]; 2] {
+		match self {
+			Self::Identity => [[1, 0], [0, 1]],
+			Self::RotateLeft => [[0, 1], [-1, 0]],
+			Self::RotateRight => [[0, -1], [1, 0]],
+			Self::Rotate180 | Self::Reflect => [[-1, 0], [0, -1]],
+			Self::ReflectHorizontal => [[-1, 0], [0, 1]],
+			Self::ReflectVertical => [[1, 0], [0, -1]],
+			Self::TransposeMain => [[0, 1], [1, 0]],
+			Self::TransposeAnti => [[0, -1], [-1, 0]],
+		}
+	}
+
+	/// Get the dimensions of a pattern with provided width and height after the transformation.
+	pub fn dimensions(self, w: u32, h: u32) -> (u32, u32) {
+		if self.matrix()[0][0] == 0 {
+			(h, w)
+		} else {
+			(w, h)
 		}
 	}
+
+	/// Get the single transformation equivalent to applying this one followed by the other.
+	pub fn then(self, other: Self) -> Self {
+		let (a, b) = (self.matrix(), other.matrix());
+		let mut product = [[0; 2]; 2];
+		for (row, product_row) in product.iter_mut().enumerate() {
+			for (col, value) in product_row.iter_mut().enumerate() {
+				*value = b[row][0] * a[0][col] + b[row][1] * a[1][col];
+			}
+		}
+		// the transformations form a group, so the product is always one of them
+		*Self::ALL.iter()
+			.find(|t| t.matrix() == product)
+			.expect("transformations should be closed under composition")
+	}
 }
 
+/// Transform provided cell of a pattern with given width and height.
+///
+/// Note: rotations by 90 degrees swap the width and height of the pattern.
 pub fn transform((x, y): (u32, u32), w: u32, h: u32, t: Transformation) -> (u32, u32) {
 	match t {
 		Transformation::Identity => (x, y),
 		Transformation::RotateRight => (h - y - 1, x),
+		Transformation::Rotate180 => (w - x - 1, h - y - 1),
 		Transformation::Reflect => (w - x - 1, h - 1 - y),
+		Transformation::ReflectHorizontal => (w - x - 1, y),
+		Transformation::ReflectVertical => (x, h - y - 1),
+		Transformation::TransposeMain => (y, x),
+		Transformation::TransposeAnti => (h - y - 1, w - x - 1),
 		Transformation::RotateLeft => (y, w - x - 1)
 	}
 }
 
+/// Get the bounding box of provided cells as `(min_x, min_y, width, height)`.
+///
+/// Note: an empty slice has a zero-sized box at the origin.
+pub fn bounding_box(cells: &[(u32, u32)]) -> (u32, u32, u32, u32) {
+	if cells.is_empty() {
+		return (0, 0, 0, 0);
+	}
+
+	let (min_x, min_y, max_x, max_y) = cells.iter().fold(
+		(u32::MAX, u32::MAX, 0, 0),
+		|(min_x, min_y, max_x, max_y), &(x, y)| (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)),
+	);
+	(min_x, min_y, max_x - min_x + 1, max_y - min_y + 1)
+}
+
+/// Shift provided cells so that the smallest x and y coordinates are 0.
+pub fn normalize(cells: &[(u32, u32)]) -> Vec<(u32, u32)> {
+	let (min_x, min_y, _, _) = bounding_box(cells);
+	cells.iter().map(|&(x, y)| (x - min_x, y - min_y)).collect()
+}
+
+/// The smallest spaceship, travels diagonally.
 pub const GLIDER: &[(u32, u32)] = &[(0, 0), (1, 0), (0, 1), (2, 1), (0, 2)];
+
+/// Period 2 oscillator.
+pub const BLINKER: &[(u32, u32)] = &[(0, 0), (1, 0), (2, 0)];
+
+/// The most common still life.
+pub const BLOCK: &[(u32, u32)] = &[(0, 0), (1, 0), (0, 1), (1, 1)];
+
+/// Still life.
+pub const BEEHIVE: &[(u32, u32)] = &[(1, 0), (2, 0), (0, 1), (3, 1), (1, 2), (2, 2)];
+
+/// Period 2 oscillator.
+pub const TOAD: &[(u32, u32)] = &[(1, 0), (2, 0), (3, 0), (0, 1), (1, 1), (2, 1)];
+
+/// Period 2 oscillator.
+pub const BEACON: &[(u32, u32)] = &[(0, 0), (1, 0), (0, 1), (3, 2), (2, 3), (3, 3)];
+
+/// Period 3 oscillator.
+pub const PULSAR: &[(u32, u32)] = &[
+	(2, 0), (3, 0), (4, 0), (8, 0), (9, 0), (10, 0),
+	(0, 2), (5, 2), (7, 2), (12, 2),
+	(0, 3), (5, 3), (7, 3), (12, 3),
+	(0, 4), (5, 4), (7, 4), (12, 4),
+	(2, 5), (3, 5), (4, 5), (8, 5), (9, 5), (10, 5),
+	(2, 7), (3, 7), (4, 7), (8, 7), (9, 7), (10, 7),
+	(0, 8), (5, 8), (7, 8), (12, 8),
+	(0, 9), (5, 9), (7, 9), (12, 9),
+	(0, 10), (5, 10), (7, 10), (12, 10),
+	(2, 12), (3, 12), (4, 12), (8, 12), (9, 12), (10, 12),
+];
+
+/// Lightweight spaceship, travels orthogonally.
+pub const LWSS: &[(u32, u32)] = &[(1, 0), (4, 0), (0, 1), (0, 2), (4, 2), (0, 3), (1, 3), (2, 3), (3, 3)];
+
+/// Gosper glider gun, emits a glider every 30 generations.
+pub const GLIDER_GUN: &[(u32, u32)] = &[
+	(24, 0),
+	(22, 1), (24, 1),
+	(12, 2), (13, 2), (20, 2), (21, 2), (34, 2), (35, 2),
+	(11, 3), (15, 3), (20, 3), (21, 3), (34, 3), (35, 3),
+	(0, 4), (1, 4), (10, 4), (16, 4), (20, 4), (21, 4),
+	(0, 5), (1, 5), (10, 5), (14, 5), (16, 5), (17, 5), (22, 5), (24, 5),
+	(10, 6), (16, 6), (24, 6),
+	(11, 7), (15, 7),
+	(12, 8), (13, 8),
+];
+
+/// Get the cells of a well-known pattern by its name.
+///
+/// Note: the cells are normalized, so the smallest x and y coordinates are 0.
+pub fn named_pattern(name: &str) -> Option<&'static [(u32, u32)]> {
+	match name {
+		"glider" => Some(GLIDER),
+		"blinker" => Some(BLINKER),
+		"block" => Some(BLOCK),
+		"beehive" => Some(BEEHIVE),
+		"toad" => Some(TOAD),
+		"beacon" => Some(BEACON),
+		"pulsar" => Some(PULSAR),
+		"lwss" => Some(LWSS),
+		"glider_gun" => Some(GLIDER_GUN),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// An asymmetric 2x3 pattern.
+	const L_SHAPE: &[(u32, u32)] = &[(0, 0), (0, 1), (0, 2), (1, 2)];
+
+	#[test]
+	fn test_rotate_180() {
+		for &cell in L_SHAPE {
+			let once = transform(cell, 2, 3, Transformation::RotateLeft);
+			let twice = transform(once, 3, 2, Transformation::RotateLeft);
+			assert_eq!(twice, transform(cell, 2, 3, Transformation::Rotate180));
+		}
+	}
+
+	#[test]
+	fn test_reflect() {
+		use Transformation::*;
+		for &cell in L_SHAPE {
+			assert_eq!(transform(cell, 2, 3, Reflect), transform(cell, 2, 3, Rotate180));
+			let mirrored = transform(cell, 2, 3, ReflectHorizontal);
+			assert_eq!(transform(mirrored, 2, 3, ReflectVertical), transform(cell, 2, 3, Rotate180));
+		}
+		assert_eq!(Reflect.then(Identity), Rotate180);
+	}
+
+	#[test]
+	fn test_then() {
+		for &a in Transformation::ALL {
+			for &b in Transformation::ALL {
+				let (w, h) = a.dimensions(2, 3);
+				for &cell in L_SHAPE {
+					let separate = transform(transform(cell, 2, 3, a), w, h, b);
+					assert_eq!(transform(cell, 2, 3, a.then(b)), separate, "{:?} then {:?}", a, b);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_then_associative() {
+		use Transformation::*;
+		assert_eq!(RotateLeft.then(Reflect).then(RotateRight), RotateLeft.then(Reflect.then(RotateRight)));
+		assert_eq!(TransposeMain.then(Rotate180).then(Reflect), TransposeMain.then(Rotate180.then(Reflect)));
+
+		for &a in Transformation::ALL {
+			for &b in Transformation::ALL {
+				for &c in Transformation::ALL {
+					assert_eq!(a.then(b).then(c), a.then(b.then(c)));
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_transpose() {
+		use Transformation::*;
+		assert_eq!(TransposeMain.then(TransposeMain), Identity);
+		assert_eq!(TransposeAnti.then(TransposeAnti), Identity);
+		assert_eq!(TransposeMain.dimensions(2, 3), (3, 2));
+		assert_eq!(TransposeAnti.dimensions(2, 3), (3, 2));
+
+		for &cell in L_SHAPE {
+			let once = transform(cell, 2, 3, TransposeMain);
+			assert_eq!(transform(once, 3, 2, TransposeMain), cell);
+			let once = transform(cell, 2, 3, TransposeAnti);
+			assert_eq!(transform(once, 3, 2, TransposeAnti), cell);
+		}
+	}
+
+	#[test]
+	fn test_group_closed() {
+		for &a in Transformation::ALL {
+			assert_eq!(a.then(Transformation::Identity), a);
+			assert!(Transformation::ALL.iter().any(|&b| a.then(b) == Transformation::Identity), "{:?} has no inverse", a);
+			let (w, h) = a.dimensions(2, 3);
+			for &b in Transformation::ALL {
+				for &cell in L_SHAPE {
+					let composed = transform(cell, 2, 3, a.then(b));
+					assert_eq!(transform(transform(cell, 2, 3, a), w, h, b), composed, "{:?} then {:?}", a, b);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_named_pattern() {
+		const NAMES: &[&str] = &[
+			"glider", "blinker", "block", "beehive", "toad", "beacon", "pulsar", "lwss", "glider_gun",
+		];
+		for name in NAMES {
+			let cells = named_pattern(name).unwrap();
+			assert_eq!(cells.iter().map(|c| c.0).min(), Some(0), "{} is not normalized", name);
+			assert_eq!(cells.iter().map(|c| c.1).min(), Some(0), "{} is not normalized", name);
+		}
+
+		assert_eq!(named_pattern("glider"), Some(GLIDER));
+		assert_eq!(named_pattern("pulsar").map(<[_]>::len), Some(48));
+		assert_eq!(named_pattern("glider_gun").map(<[_]>::len), Some(36));
+		assert_eq!(named_pattern("unknown"), None);
+	}
+
+	#[test]
+	fn test_bounding_box() {
+		assert_eq!(bounding_box(&[]), (0, 0, 0, 0));
+		assert_eq!(bounding_box(&[(3, 4)]), (3, 4, 1, 1));
+		assert_eq!(bounding_box(GLIDER), (0, 0, 3, 3));
+		assert_eq!(bounding_box(&[(5, 2), (2, 7), (4, 4)]), (2, 2, 4, 6));
+	}
+
+	#[test]
+	fn test_normalize() {
+		assert_eq!(normalize(&[]), &[]);
+		assert_eq!(normalize(GLIDER), GLIDER);
+		assert_eq!(normalize(&[(5, 2), (2, 7), (4, 4)]), &[(3, 0), (0, 5), (2, 2)]);
+	}
+
+	#[test]
+	fn test_transformations_distinct() {
+		for (i, &a) in Transformation::ALL.iter().enumerate() {
+			for &b in &Transformation::ALL[i + 1..] {
+				let mut a_cells: Vec<_> = L_SHAPE.iter().map(|&c| transform(c, 2, 3, a)).collect();
+				let mut b_cells: Vec<_> = L_SHAPE.iter().map(|&c| transform(c, 2, 3, b)).collect();
+				a_cells.sort_unstable();
+				b_cells.sort_unstable();
+				assert_ne!(a_cells, b_cells, "{:?} and {:?} are equivalent", a, b);
+			}
+		}
+	}
+}
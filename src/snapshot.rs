@@ -0,0 +1,107 @@
+//! Compact binary snapshot of a universe.
+//!
+//! The snapshot consists of a 16 byte little-endian header of width (u32), height (u32)
+//! and generation (u64), followed by the packed cell bytes.
+
+use std::convert::TryInto;
+use std::fmt;
+
+use wasm_bindgen::JsValue;
+
+/// Size of the snapshot header in bytes.
+const HEADER_SIZE: usize = 16;
+
+/// Reasons a snapshot may fail to decode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+	/// The data is too short to contain a header.
+	Truncated,
+	/// The dimensions are zero or too large.
+	InvalidDimensions,
+	/// The number of cell bytes does not match the dimensions.
+	LengthMismatch { expected: usize, actual: usize },
+	/// The data is not a supported or valid image.
+	InvalidImage,
+}
+
+impl fmt::Display for DecodeError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Truncated => write!(f, "snapshot is too short to contain a header"),
+			Self::InvalidDimensions => write!(f, "snapshot has invalid dimensions"),
+			Self::LengthMismatch { expected, actual } => {
+				write!(f, "snapshot should have {} bytes of cells, but has {}", expected, actual)
+			},
+			Self::InvalidImage => write!(f, "data is not a valid image"),
+		}
+	}
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for JsValue {
+	fn from(err: DecodeError) -> Self {
+		JsValue::from_str(&err.to_string())
+	}
+}
+
+/// Decoded snapshot contents.
+pub struct Snapshot<'a> {
+	pub width: u32,
+	pub height: u32,
+	pub generation: u64,
+	pub cells: &'a [u8],
+}
+
+/// Encode a snapshot of provided universe state.
+pub fn encode(width: u32, height: u32, generation: u64, cells: &[u8]) -> Vec<u8> {
+	let mut bytes = Vec::with_capacity(HEADER_SIZE + cells.len());
+	bytes.extend_from_slice(&width.to_le_bytes());
+	bytes.extend_from_slice(&height.to_le_bytes());
+	bytes.extend_from_slice(&generation.to_le_bytes());
+	bytes.extend_from_slice(cells);
+	bytes
+}
+
+/// Decode and validate a snapshot.
+pub fn decode(bytes: &[u8]) -> Result<Snapshot<'_>, DecodeError> {
+	if bytes.len() < HEADER_SIZE {
+		return Err(DecodeError::Truncated);
+	}
+	let (header, cells) = bytes.split_at(HEADER_SIZE);
+	let width = u32::from_le_bytes(header[0..4].try_into().unwrap());
+	let height = u32::from_le_bytes(header[4..8].try_into().unwrap());
+	let generation = u64::from_le_bytes(header[8..16].try_into().unwrap());
+
+	let len = width.checked_mul(height).filter(|&len| len > 0).ok_or(DecodeError::InvalidDimensions)?;
+	let expected = (len as usize).div_ceil(8);
+	if cells.len() != expected {
+		return Err(DecodeError::LengthMismatch { expected, actual: cells.len() });
+	}
+
+	Ok(Snapshot { width, height, generation, cells })
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn test_decode() {
+		let bytes = encode(3, 5, 7, &[1, 2]);
+		let snapshot = decode(&bytes).unwrap();
+
+		assert_eq!((snapshot.width, snapshot.height, snapshot.generation), (3, 5, 7));
+		assert_eq!(snapshot.cells, &[1, 2]);
+	}
+
+	#[test]
+	fn test_decode_errors() {
+		let bytes = encode(3, 5, 7, &[1, 2]);
+
+		assert_eq!(decode(&bytes[..10]).err(), Some(DecodeError::Truncated));
+		assert_eq!(decode(&bytes[..17]).err(), Some(DecodeError::LengthMismatch { expected: 2, actual: 1 }));
+		assert_eq!(decode(&encode(0, 5, 0, &[])).err(), Some(DecodeError::InvalidDimensions));
+		assert_eq!(decode(&encode(u32::MAX, 5, 0, &[])).err(), Some(DecodeError::InvalidDimensions));
+	}
+}